@@ -3,8 +3,13 @@
 //! Feature crates call `register_feature` at startup to register their handler
 //! functions, and the CLI calls `process_input` to parse and dispatch commands.
 use std::collections::HashMap;
+use std::cmp::Reverse;
 use std::fmt::{Display, Formatter};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::{Mutex, OnceLock};
+use sha2::{Digest, Sha256};
 
 /// Result type returned by `process_input`.
 ///
@@ -37,6 +42,122 @@ impl Display for CoreError {
 
 impl std::error::Error for CoreError {}
 
+/// File type of a directory entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+    Other,
+}
+
+/// One directory listing entry with metadata, shared by local and remote listings.
+///
+/// Fields the source cannot provide (e.g. uid/gid on Windows) are `None`.
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    pub name: String,
+    pub kind: FileKind,
+    pub size: u64,
+    /// Permission bits only (`mode & 0o7777`).
+    pub perm: Option<u32>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    /// Modification time in unix seconds.
+    pub mtime: Option<u64>,
+}
+
+impl DirEntry {
+    /// `ls -l` style line: mode, uid, gid, size, mtime (UTC), name.
+    pub fn long_format(&self) -> String {
+        let id = |v: Option<u32>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".into());
+        let time = self.mtime.map(format_unix_time).unwrap_or_else(|| "-".repeat(16));
+        format!(
+            "{} {:>6} {:>6} {:>12} {} {}",
+            mode_string(self.kind, self.perm),
+            id(self.uid),
+            id(self.gid),
+            self.size,
+            time,
+            self.name
+        )
+    }
+}
+
+/// Sort key for directory listings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Name,
+    Size,
+    Time,
+}
+
+impl FromStr for SortBy {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(SortBy::Name),
+            "size" => Ok(SortBy::Size),
+            "time" => Ok(SortBy::Time),
+            other => Err(CoreError::InvalidArgs(format!(
+                "unknown sort key: {} (use name|size|time)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Sort entries like `ls`: by name ascending, or largest/newest first.
+pub fn sort_entries(entries: &mut [DirEntry], by: SortBy, reverse: bool) {
+    match by {
+        SortBy::Name => entries.sort_by(|a, b| a.name.cmp(&b.name)),
+        SortBy::Size => entries.sort_by_key(|e| (Reverse(e.size), e.name.clone())),
+        SortBy::Time => entries.sort_by_key(|e| (Reverse(e.mtime), e.name.clone())),
+    }
+    if reverse {
+        entries.reverse();
+    }
+}
+
+/// Source and destination SHA-256 digests (lowercase hex) that did not match.
+///
+/// Feature crates convert this into their own checksum error variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestMismatch {
+    pub path: PathBuf,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Lowercase hex encoding, e.g. for SHA-256 digests.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// SHA-256 of everything `src` yields, as lowercase hex.
+pub fn sha256_hex<R: Read>(mut src: R) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let n = src.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Compare the digest of `path` after a copy (`actual`) with the source's (`expected`).
+pub fn check_digest(path: &Path, expected: String, actual: String) -> Result<(), DigestMismatch> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(DigestMismatch { path: path.to_path_buf(), expected, actual })
+    }
+}
+
 // Feature registry
 type FeatureFn = fn(&[String]) -> Result<(), CoreError>;
 
@@ -146,6 +267,53 @@ fn tokenize(input: &str) -> Vec<String> {
     out
 }
 
+/// Renders `drwxr-xr-x` style mode strings.
+fn mode_string(kind: FileKind, perm: Option<u32>) -> String {
+    let mut out = String::with_capacity(10);
+    out.push(match kind {
+        FileKind::Dir => 'd',
+        FileKind::Symlink => 'l',
+        FileKind::File => '-',
+        FileKind::Other => '?',
+    });
+    match perm {
+        Some(p) => {
+            for shift in [6, 3, 0] {
+                let bits = (p >> shift) & 0o7;
+                out.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+                out.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+                out.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+            }
+        }
+        None => out.push_str("?????????"),
+    }
+    out
+}
+
+/// Formats unix seconds as `YYYY-MM-DD HH:MM` (UTC) without pulling in a date crate.
+fn format_unix_time(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // civil_from_days (Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        (rem % 3_600) / 60
+    )
+}
+
 /// Checks if the feature is implemented/available.
 #[allow(dead_code)]
 fn feature_exists(name: &str) -> bool {
@@ -194,5 +362,46 @@ mod tests {
         let out = process_input("echo 'hello world'").unwrap();
         assert_eq!(out.code, 0);
     }
+
+    #[test]
+    fn digests_are_hex_and_compared() {
+        let empty = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert_eq!(sha256_hex(&b""[..]).unwrap(), empty);
+        assert_eq!(hex(&[0x00, 0x0f, 0xab]), "000fab");
+
+        let path = Path::new("dest.bin");
+        assert!(check_digest(path, empty.into(), empty.into()).is_ok());
+        let err = check_digest(path, empty.into(), "00".into()).unwrap_err();
+        assert_eq!(err, DigestMismatch { path: path.into(), expected: empty.into(), actual: "00".into() });
+    }
+
+    fn entry(name: &str, size: u64, mtime: u64) -> DirEntry {
+        DirEntry {
+            name: name.into(),
+            kind: FileKind::File,
+            size,
+            perm: Some(0o644),
+            uid: Some(1000),
+            gid: Some(1000),
+            mtime: Some(mtime),
+        }
+    }
+
+    #[test]
+    fn listing_sort_and_long_format() {
+        let mut v = vec![entry("b", 10, 300), entry("a", 30, 100), entry("c", 20, 200)];
+        sort_entries(&mut v, SortBy::Size, false);
+        assert_eq!(v.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), ["a", "c", "b"]);
+        sort_entries(&mut v, SortBy::Time, false);
+        assert_eq!(v[0].name, "b");
+        sort_entries(&mut v, SortBy::Name, true);
+        assert_eq!(v[0].name, "c");
+        assert!("bogus".parse::<SortBy>().is_err());
+
+        let line = entry("x.txt", 42, 1_755_086_400).long_format();
+        assert!(line.starts_with("-rw-r--r--"));
+        assert!(line.contains("2025-08-13 12:00"));
+        assert!(line.ends_with(" x.txt"));
+    }
 }
 
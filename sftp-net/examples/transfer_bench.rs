@@ -0,0 +1,120 @@
+// Throughput benchmark: purana 64 KiB read-then-write loop (ssh2 `File` pe, ek
+// request in flight) vs. pipelined transfers alag alag `chunk_size`/`window` pe.
+// Same SFTP_TEST_* env vars as tests/smoke.rs; SFTP_BENCH_MB sets the file size.
+// Baseline key file ya password auth hi support karta hai.
+//
+//   SFTP_TEST_HOST=127.0.0.1 SFTP_TEST_USER=me SFTP_TEST_KEY=~/.ssh/id_ed25519 \
+//   SFTP_TEST_DIR=/tmp cargo run --release -p sftp-net --example transfer_bench
+
+use sftp_net::{Algorithms, Auth, HostKeyPolicy, RetryPolicy, SftpClient, SshConfig, TransferOptions};
+use std::env;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::time::Instant;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let host = env::var("SFTP_TEST_HOST").map_err(|_| "set SFTP_TEST_HOST")?;
+    let port = env::var("SFTP_TEST_PORT").ok().and_then(|s| s.parse().ok()).unwrap_or(22);
+    let user = env::var("SFTP_TEST_USER").map_err(|_| "set SFTP_TEST_USER")?;
+    let dir = env::var("SFTP_TEST_DIR").unwrap_or_else(|_| "/tmp".to_string());
+    let mb: usize = env::var("SFTP_BENCH_MB").ok().and_then(|s| s.parse().ok()).unwrap_or(64);
+
+    let auth = if let Ok(k) = env::var("SFTP_TEST_KEY") {
+        Auth::KeyFile { username: user.clone(), private_key: k.into(), passphrase: env::var("SFTP_TEST_KEY_PASS").ok(), public_key: None }
+    } else {
+        let pw = env::var("SFTP_TEST_PASSWORD").map_err(|_| "set SFTP_TEST_KEY or SFTP_TEST_PASSWORD")?;
+        Auth::Password { username: user.clone(), password: pw }
+    };
+    let cfg = SshConfig {
+        host: host.clone(),
+        alias: None,
+        port,
+        auth: auth.clone(),
+        known_hosts: env::var("SFTP_TEST_KNOWN_HOSTS").ok().map(Into::into),
+        global_known_hosts: Vec::new(),
+        hash_known_hosts: false,
+        hostkey_policy: HostKeyPolicy::InsecureIgnore,
+        timeout_ms: Some(30_000),
+        jump_hosts: Vec::new(),
+        proxy_command: None,
+        proxy: None,
+        keepalive_secs: None,
+        retry: RetryPolicy::default(),
+        algorithms: Algorithms::default(),
+    };
+    let client = SftpClient::connect(&cfg)?;
+
+    // Local test file
+    let local = env::temp_dir().join("sftp_bench_src.bin");
+    let back = env::temp_dir().join("sftp_bench_back.bin");
+    let mut f = std::fs::File::create(&local)?;
+    let block: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+    for _ in 0..mb {
+        f.write_all(&block)?;
+    }
+    drop(f);
+    let remote = format!("{}/sftp_bench.bin", dir.trim_end_matches('/'));
+
+    let expect = (mb * 1024 * 1024) as u64;
+    println!("{:>22}  {:>14}  {:>16}", "mode", "upload MiB/s", "download MiB/s");
+
+    // Baseline: wahi loop jo pipelining se pehle tha, alag plain ssh2 session pe
+    let sftp = baseline_session(&host, port, &auth)?;
+    let t = Instant::now();
+    copy_64k(&mut std::fs::File::open(&local)?, &mut sftp.create(Path::new(&remote))?)?;
+    let up = mb as f64 / t.elapsed().as_secs_f64();
+    let t = Instant::now();
+    copy_64k(&mut sftp.open(Path::new(&remote))?, &mut std::fs::File::create(&back)?)?;
+    let down = mb as f64 / t.elapsed().as_secs_f64();
+    check_len(&back, expect)?;
+    println!("{:>22}  {up:>14.1}  {down:>16.1}", "64 KiB loop");
+
+    for (chunk_kib, window) in [(32, 1), (32, 16), (32, 64), (32, 256), (255, 16)] {
+        let opts = TransferOptions { chunk_size: chunk_kib * 1024, window, ..Default::default() };
+        let t = Instant::now();
+        client.upload_file_with(&local, &remote, &opts)?;
+        let up = mb as f64 / t.elapsed().as_secs_f64();
+        let t = Instant::now();
+        client.download_file_with(&remote, &back, &opts)?;
+        let down = mb as f64 / t.elapsed().as_secs_f64();
+        check_len(&back, expect)?;
+        println!("{:>22}  {up:>14.1}  {down:>16.1}", format!("{chunk_kib} KiB x {window}"));
+    }
+
+    client.remove(&remote)?;
+    std::fs::remove_file(&local)?;
+    std::fs::remove_file(&back)?;
+    Ok(())
+}
+
+/// Pipelining se pehle ka copy: 64 KiB padho, likho, repeat.
+fn copy_64k(src: &mut impl Read, dst: &mut impl Write) -> std::io::Result<()> {
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = src.read(&mut buf)?;
+        if n == 0 {
+            return Ok(());
+        }
+        dst.write_all(&buf[..n])?;
+    }
+}
+
+fn baseline_session(host: &str, port: u16, auth: &Auth) -> Result<ssh2::Sftp, Box<dyn std::error::Error>> {
+    let mut sess = ssh2::Session::new()?;
+    sess.set_tcp_stream(TcpStream::connect((host, port))?);
+    sess.handshake()?;
+    match auth {
+        Auth::KeyFile { username, private_key, passphrase, .. } => sess.userauth_pubkey_file(username, None, private_key, passphrase.as_deref())?,
+        Auth::Password { username, password } => sess.userauth_password(username, password)?,
+        _ => return Err("baseline supports key file or password auth only".into()),
+    }
+    Ok(sess.sftp()?)
+}
+
+fn check_len(path: &Path, expect: u64) -> Result<(), Box<dyn std::error::Error>> {
+    if std::fs::metadata(path)?.len() != expect {
+        return Err("downloaded size mismatch".into());
+    }
+    Ok(())
+}
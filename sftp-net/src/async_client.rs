@@ -0,0 +1,547 @@
+//! Tokio ke liye async client.
+//!
+//! ssh2 blocking hai, isliye har operation tokio ke blocking pool pe chalta hai:
+//! runtime ke worker threads kabhi block nahi hote aur bahut saare transfers ek
+//! hi runtime pe saath chal sakte hain. Transfer ka future drop ho (`select!`,
+//! `timeout`, task abort) to transfer agle batch pe `NetError::Cancelled` ke saath
+//! ruk jaata hai; chhote operations (stat, rename, ...) background me poore hote hain.
+
+use crate::{DirEntry, FileResult, FileStat, Negotiated, NetError, SftpClient, SshConfig, TransferOptions};
+use std::future::Future;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::task::JoinHandle;
+
+/// Streaming handles ek blocking call me itna padhte/likhte hain; libssh2 isse
+/// kai SFTP requests me baant ke pipeline karta hai.
+const STREAM_CHUNK: usize = 256 * 1024;
+
+/// `SftpClient` ka async roop. Clones same connection aur SFTP channel share
+/// karte hain; alag channel chahiye to `open_channel`.
+#[derive(Clone)]
+pub struct AsyncSftpClient {
+    inner: Arc<SftpClient>,
+}
+
+impl AsyncSftpClient {
+    /// `SftpClient::connect` jaisa (retry policy samet), blocking pool pe.
+    pub async fn connect(cfg: &SshConfig) -> Result<Self, NetError> {
+        let cfg = cfg.clone();
+        let client = blocking(move || SftpClient::connect(&cfg)).await?;
+        Ok(Self::from_blocking(client))
+    }
+
+    /// Pehle se bana blocking client wrap karo (e.g. `SftpClient::connect_over` se).
+    pub fn from_blocking(client: SftpClient) -> Self {
+        Self { inner: Arc::new(client) }
+    }
+
+    /// Andar wala blocking client, sync code ke saath share karne ke liye.
+    pub fn blocking_client(&self) -> &SftpClient {
+        &self.inner
+    }
+
+    /// Same SSH connection pe naya SFTP channel (parallel transfers ke liye).
+    pub async fn open_channel(&self) -> Result<Self, NetError> {
+        self.run(|c| c.open_channel()).await.map(Self::from_blocking)
+    }
+
+    pub fn negotiated(&self) -> Negotiated {
+        self.inner.negotiated()
+    }
+
+    /// Koi bhi blocking operation blocking pool pe chalao.
+    pub async fn run<T, F>(&self, op: F) -> Result<T, NetError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SftpClient) -> Result<T, NetError> + Send + 'static,
+    {
+        let client = Arc::clone(&self.inner);
+        blocking(move || op(&client)).await
+    }
+
+    /// Transfer chalao; future drop hone pe sirf isi transfer ka token cancel hota hai.
+    async fn transfer<T, F>(&self, opts: &TransferOptions, op: F) -> Result<T, NetError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SftpClient, &TransferOptions) -> Result<T, NetError> + Send + 'static,
+    {
+        let mut opts = opts.clone();
+        opts.cancel = opts.cancel.child();
+        let _guard = CancelOnDrop(opts.cancel.clone());
+        self.run(move |c| op(c, &opts)).await
+    }
+
+    pub async fn upload_file(&self, local: impl Into<PathBuf>, remote: impl Into<PathBuf>) -> Result<(), NetError> {
+        self.upload_file_with(local, remote, &TransferOptions::default()).await
+    }
+
+    pub async fn upload_file_with(&self, local: impl Into<PathBuf>, remote: impl Into<PathBuf>, opts: &TransferOptions) -> Result<(), NetError> {
+        let (local, remote) = (local.into(), remote.into());
+        self.transfer(opts, move |c, o| c.upload_file_with(&local, &remote, o)).await
+    }
+
+    pub async fn download_file(&self, remote: impl Into<PathBuf>, local: impl Into<PathBuf>) -> Result<(), NetError> {
+        self.download_file_with(remote, local, &TransferOptions::default()).await
+    }
+
+    pub async fn download_file_with(&self, remote: impl Into<PathBuf>, local: impl Into<PathBuf>, opts: &TransferOptions) -> Result<(), NetError> {
+        let (remote, local) = (remote.into(), local.into());
+        self.transfer(opts, move |c, o| c.download_file_with(&remote, &local, o)).await
+    }
+
+    pub async fn download_segmented(&self, remote: impl Into<PathBuf>, local: impl Into<PathBuf>, segments: usize, opts: &TransferOptions) -> Result<(), NetError> {
+        let (remote, local) = (remote.into(), local.into());
+        self.transfer(opts, move |c, o| c.download_segmented(&remote, &local, segments, o)).await
+    }
+
+    pub async fn upload_dir(&self, local_dir: impl Into<PathBuf>, remote_dir: impl Into<PathBuf>) -> Result<Vec<FileResult>, NetError> {
+        self.upload_dir_with(local_dir, remote_dir, &TransferOptions::default()).await
+    }
+
+    pub async fn upload_dir_with(&self, local_dir: impl Into<PathBuf>, remote_dir: impl Into<PathBuf>, opts: &TransferOptions) -> Result<Vec<FileResult>, NetError> {
+        let (local, remote) = (local_dir.into(), remote_dir.into());
+        self.transfer(opts, move |c, o| c.upload_dir_with(&local, &remote, o)).await
+    }
+
+    pub async fn download_dir(&self, remote_dir: impl Into<PathBuf>, local_dir: impl Into<PathBuf>) -> Result<Vec<FileResult>, NetError> {
+        self.download_dir_with(remote_dir, local_dir, &TransferOptions::default()).await
+    }
+
+    pub async fn download_dir_with(&self, remote_dir: impl Into<PathBuf>, local_dir: impl Into<PathBuf>, opts: &TransferOptions) -> Result<Vec<FileResult>, NetError> {
+        let (remote, local) = (remote_dir.into(), local_dir.into());
+        self.transfer(opts, move |c, o| c.download_dir_with(&remote, &local, o)).await
+    }
+
+    pub async fn list_dir(&self, remote_dir: impl Into<PathBuf>) -> Result<Vec<String>, NetError> {
+        let dir = remote_dir.into();
+        self.run(move |c| c.list_dir(&dir)).await
+    }
+
+    pub async fn list_entries(&self, remote_dir: impl Into<PathBuf>) -> Result<Vec<DirEntry>, NetError> {
+        let dir = remote_dir.into();
+        self.run(move |c| c.list_entries(&dir)).await
+    }
+
+    pub async fn mkdir(&self, dir: impl Into<PathBuf>, mode: i32) -> Result<(), NetError> {
+        let dir = dir.into();
+        self.run(move |c| c.mkdir(&dir, mode)).await
+    }
+
+    pub async fn mkdir_all(&self, dir: impl Into<PathBuf>, mode: i32) -> Result<(), NetError> {
+        let dir = dir.into();
+        self.run(move |c| c.mkdir_all(&dir, mode)).await
+    }
+
+    pub async fn rmdir(&self, dir: impl Into<PathBuf>) -> Result<(), NetError> {
+        let dir = dir.into();
+        self.run(move |c| c.rmdir(&dir)).await
+    }
+
+    pub async fn remove(&self, file: impl Into<PathBuf>) -> Result<(), NetError> {
+        let file = file.into();
+        self.run(move |c| c.remove(&file)).await
+    }
+
+    pub async fn rename(&self, from: impl Into<PathBuf>, to: impl Into<PathBuf>, overwrite: bool) -> Result<(), NetError> {
+        let (from, to) = (from.into(), to.into());
+        self.run(move |c| c.rename(&from, &to, overwrite)).await
+    }
+
+    pub async fn stat(&self, path: impl Into<PathBuf>) -> Result<FileStat, NetError> {
+        let path = path.into();
+        self.run(move |c| c.stat(&path)).await
+    }
+
+    pub async fn lstat(&self, path: impl Into<PathBuf>) -> Result<FileStat, NetError> {
+        let path = path.into();
+        self.run(move |c| c.lstat(&path)).await
+    }
+
+    pub async fn setstat(&self, path: impl Into<PathBuf>, stat: FileStat) -> Result<(), NetError> {
+        let path = path.into();
+        self.run(move |c| c.setstat(&path, stat)).await
+    }
+
+    pub async fn chmod(&self, path: impl Into<PathBuf>, mode: u32) -> Result<(), NetError> {
+        let path = path.into();
+        self.run(move |c| c.chmod(&path, mode)).await
+    }
+
+    pub async fn chown(&self, path: impl Into<PathBuf>, uid: u32, gid: u32) -> Result<(), NetError> {
+        let path = path.into();
+        self.run(move |c| c.chown(&path, uid, gid)).await
+    }
+
+    pub async fn utimes(&self, path: impl Into<PathBuf>, atime: u64, mtime: u64) -> Result<(), NetError> {
+        let path = path.into();
+        self.run(move |c| c.utimes(&path, atime, mtime)).await
+    }
+
+    pub async fn symlink(&self, target: impl Into<PathBuf>, link: impl Into<PathBuf>) -> Result<(), NetError> {
+        let (target, link) = (target.into(), link.into());
+        self.run(move |c| c.symlink(&target, &link)).await
+    }
+
+    pub async fn readlink(&self, link: impl Into<PathBuf>) -> Result<PathBuf, NetError> {
+        let link = link.into();
+        self.run(move |c| c.readlink(&link)).await
+    }
+
+    /// Remote file ko `AsyncRead` ke roop me kholo (e.g. `tokio::io::copy` ke liye).
+    pub async fn open_read(&self, path: impl Into<PathBuf>) -> Result<AsyncRemoteReader, NetError> {
+        let path = path.into();
+        let file = self.run(move |c| c.open_read(&path)).await?;
+        Ok(AsyncRemoteReader(Stream::new(file)))
+    }
+
+    /// Remote file ko `AsyncWrite` ke roop me kholo (create + truncate, ya append).
+    /// Writes background me jaate hain; error agle write/flush pe milta hai, isliye
+    /// aakhir me `shutdown()` (ya `flush()`) zaroor await karo.
+    pub async fn open_write(&self, path: impl Into<PathBuf>, append: bool) -> Result<AsyncRemoteWriter, NetError> {
+        let path = path.into();
+        let file = self.run(move |c| c.open_write(&path, append)).await?;
+        Ok(AsyncRemoteWriter(Stream::new(file)))
+    }
+}
+
+/// Blocking pool pe chalao. Runtime band ho raha ho to `Cancelled`; panic caller tak jaata hai.
+async fn blocking<T, F>(op: F) -> Result<T, NetError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, NetError> + Send + 'static,
+{
+    match tokio::task::spawn_blocking(op).await {
+        Ok(res) => res,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(_) => Err(NetError::Cancelled),
+    }
+}
+
+/// Future drop hone pe (ya kaam poora hone pe, tab bekaar) token cancel karo.
+struct CancelOnDrop(crate::CancelToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Streaming read handle; `AsyncSftpClient::open_read` deta hai.
+pub struct AsyncRemoteReader(Stream<ssh2::File>);
+
+/// Streaming write handle; `AsyncSftpClient::open_write` deta hai.
+pub struct AsyncRemoteWriter(Stream<ssh2::File>);
+
+impl AsyncRead for AsyncRemoteReader {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().0.poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for AsyncRemoteWriter {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.get_mut().0.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().0.poll_idle(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().0.poll_close(cx)
+    }
+}
+
+impl Drop for AsyncRemoteReader {
+    fn drop(&mut self) {
+        self.0.close_in_background();
+    }
+}
+
+impl Drop for AsyncRemoteWriter {
+    fn drop(&mut self) {
+        self.0.close_in_background();
+    }
+}
+
+/// Blocking file + buffer; ek waqt me ek hi blocking call chalti hai (tokio::fs::File jaisa).
+struct Chunk<F> {
+    file: F,
+    data: Vec<u8>,
+    pos: usize,
+}
+
+enum Stream<F> {
+    /// `None` = pichli blocking call panic/abort ho gayi, handle ab kaam ka nahi.
+    Idle(Option<Chunk<F>>),
+    Busy(JoinHandle<(Chunk<F>, io::Result<()>)>),
+    /// Handle blocking pool pe band ho raha hai.
+    Closing(JoinHandle<()>),
+}
+
+impl<F: Send + 'static> Stream<F> {
+    fn new(file: F) -> Self {
+        Stream::Idle(Some(Chunk { file, data: Vec::new(), pos: 0 }))
+    }
+
+    /// Chal rahi blocking call khatam hone do; uska error yahin milta hai.
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Stream::Busy(handle) = self {
+            let done = ready!(Pin::new(handle).poll(cx));
+            let (chunk, res) = match done {
+                Ok(pair) => pair,
+                Err(e) => {
+                    *self = Stream::Idle(None);
+                    return Poll::Ready(Err(io::Error::other(e)));
+                }
+            };
+            *self = Stream::Idle(Some(chunk));
+            res?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn take(&mut self) -> io::Result<Chunk<F>> {
+        match self {
+            Stream::Idle(slot) => slot.take().ok_or_else(|| io::Error::other("remote file handle closed or lost after a failed operation")),
+            Stream::Busy(_) | Stream::Closing(_) => unreachable!("take() only after poll_idle"),
+        }
+    }
+
+    /// File ko blocking pool pe band karo: ssh2 `File` ka drop server ko CLOSE
+    /// bhej ke reply ka wait karta hai, jo runtime thread pe nahi hona chahiye.
+    fn poll_close(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            match self {
+                Stream::Closing(handle) => {
+                    let done = ready!(Pin::new(handle).poll(cx));
+                    *self = Stream::Idle(None);
+                    return Poll::Ready(done.map_err(io::Error::other));
+                }
+                Stream::Idle(None) => return Poll::Ready(Ok(())),
+                _ => {
+                    ready!(self.poll_idle(cx))?;
+                    let chunk = self.take()?;
+                    *self = Stream::Closing(tokio::task::spawn_blocking(move || drop(chunk)));
+                }
+            }
+        }
+    }
+
+    /// Drop pe `poll_close` jaisa, bina wait kiye. Chal rahi call ho to uske
+    /// khatam hone ka wait bhi blocking pool pe. Runtime ke bahar seedha drop.
+    fn close_in_background(&mut self) {
+        let Ok(rt) = tokio::runtime::Handle::try_current() else { return };
+        match std::mem::replace(self, Stream::Idle(None)) {
+            Stream::Idle(Some(chunk)) => drop(rt.spawn_blocking(move || drop(chunk))),
+            Stream::Busy(handle) => {
+                let wait = rt.clone();
+                drop(rt.spawn_blocking(move || drop(wait.block_on(handle))));
+            }
+            Stream::Idle(None) | Stream::Closing(_) => {}
+        }
+    }
+
+    fn spawn(&mut self, mut chunk: Chunk<F>, op: fn(&mut Chunk<F>) -> io::Result<()>) {
+        *self = Stream::Busy(tokio::task::spawn_blocking(move || {
+            let res = op(&mut chunk);
+            (chunk, res)
+        }));
+    }
+}
+
+impl<F: Read + Send + 'static> Stream<F> {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        loop {
+            let was_busy = matches!(self, Stream::Busy(_));
+            ready!(self.poll_idle(cx))?;
+            let mut chunk = self.take()?;
+            if chunk.pos < chunk.data.len() {
+                let n = buf.remaining().min(chunk.data.len() - chunk.pos);
+                buf.put_slice(&chunk.data[chunk.pos..chunk.pos + n]);
+                chunk.pos += n;
+                *self = Stream::Idle(Some(chunk));
+                return Poll::Ready(Ok(()));
+            }
+            if was_busy {
+                // Abhi padha aur kuch nahi mila: EOF
+                *self = Stream::Idle(Some(chunk));
+                return Poll::Ready(Ok(()));
+            }
+            self.spawn(chunk, |c| {
+                c.data.resize(STREAM_CHUNK, 0);
+                c.pos = 0;
+                let res = c.file.read(&mut c.data);
+                c.data.truncate(*res.as_ref().unwrap_or(&0));
+                res.map(|_| ())
+            });
+        }
+    }
+}
+
+impl<F: Write + Send + 'static> Stream<F> {
+    fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        ready!(self.poll_idle(cx))?;
+        let mut chunk = self.take()?;
+        let n = buf.len().min(STREAM_CHUNK);
+        chunk.data.clear();
+        chunk.data.extend_from_slice(&buf[..n]);
+        self.spawn(chunk, |c| c.file.write_all(&c.data));
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Test ke liye `Stream` ko seedha AsyncRead/AsyncWrite banao.
+    struct TestStream<F: Send + 'static>(Stream<F>);
+
+    impl<F: Send + 'static> Drop for TestStream<F> {
+        fn drop(&mut self) {
+            self.0.close_in_background();
+        }
+    }
+
+    impl<F: Read + Send + Unpin + 'static> AsyncRead for TestStream<F> {
+        fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().0.poll_read(cx, buf)
+        }
+    }
+
+    impl<F: Write + Send + Unpin + 'static> AsyncWrite for TestStream<F> {
+        fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            self.get_mut().0.poll_write(cx, buf)
+        }
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().0.poll_idle(cx)
+        }
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().0.poll_close(cx)
+        }
+    }
+
+    /// Shared buffer jisme blocking pool se likha jaata hai.
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread().build().unwrap()
+    }
+
+    #[test]
+    fn streams_round_trip_through_blocking_pool() {
+        let data: Vec<u8> = (0..STREAM_CHUNK * 2 + 1234).map(|i| (i % 251) as u8).collect();
+        runtime().block_on(async {
+            let mut reader = TestStream(Stream::new(Cursor::new(data.clone())));
+            let mut read_back = Vec::new();
+            reader.read_to_end(&mut read_back).await.unwrap();
+            assert_eq!(read_back, data);
+
+            let sink = Shared::default();
+            let mut writer = TestStream(Stream::new(sink.clone()));
+            writer.write_all(&data).await.unwrap();
+            writer.shutdown().await.unwrap();
+            assert_eq!(*sink.0.lock().unwrap(), data);
+        });
+    }
+
+    #[test]
+    fn write_errors_surface_on_flush() {
+        struct Broken;
+        impl Write for Broken {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "gone"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        runtime().block_on(async {
+            let mut writer = TestStream(Stream::new(Broken));
+            assert_eq!(writer.write(b"abc").await.unwrap(), 3);
+            assert_eq!(writer.flush().await.unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+        });
+    }
+
+    /// ssh2 `File` ki jagah: drop kis thread pe hua wo record karta hai.
+    struct DropProbe(Arc<Mutex<Option<std::thread::ThreadId>>>);
+
+    impl Read for DropProbe {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Ok(0)
+        }
+    }
+
+    impl Write for DropProbe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Drop for DropProbe {
+        fn drop(&mut self) {
+            *self.0.lock().unwrap() = Some(std::thread::current().id());
+        }
+    }
+
+    /// `f` ko runtime pe chalao; probe wali file kis thread pe drop hui.
+    fn closed_on<Fut: Future<Output = ()>>(f: impl FnOnce(TestStream<DropProbe>) -> Fut) -> std::thread::ThreadId {
+        let slot = Arc::new(Mutex::new(None));
+        let rt = runtime();
+        rt.block_on(f(TestStream(Stream::new(DropProbe(slot.clone())))));
+        // Runtime drop blocking tasks ke khatam hone ka wait karta hai
+        drop(rt);
+        slot.lock().unwrap().expect("file handle was never dropped")
+    }
+
+    #[test]
+    fn handle_is_closed_off_the_runtime_thread() {
+        let here = std::thread::current().id();
+        // shutdown() close ka wait karta hai; uske baad handle kaam ka nahi
+        let on = closed_on(|mut w| async move {
+            w.write_all(b"abc").await.unwrap();
+            w.shutdown().await.unwrap();
+            assert_eq!(w.write(b"x").await.unwrap_err().kind(), io::ErrorKind::Other);
+        });
+        assert_ne!(on, here);
+        // Idle handle drop, write chal rahi ho tab drop, reader drop
+        assert_ne!(closed_on(|w| async move { drop(w) }), here);
+        assert_ne!(closed_on(|mut w| async move { assert_eq!(w.write(b"abc").await.unwrap(), 3) }), here);
+        assert_ne!(closed_on(|mut r| async move { assert_eq!(r.read(&mut [0u8; 4]).await.unwrap(), 0) }), here);
+    }
+
+    #[test]
+    fn dropped_transfer_cancels_only_its_own_token() {
+        let parent = crate::CancelToken::default();
+        let child = parent.child();
+        drop(CancelOnDrop(child.clone()));
+        assert!(child.is_cancelled() && !parent.is_cancelled());
+
+        let other = parent.child();
+        parent.cancel();
+        assert!(other.is_cancelled());
+    }
+}
@@ -0,0 +1,574 @@
+//! OpenSSH `known_hosts` files: plain aur hashed (`|1|salt|hash`) host
+//! patterns, `@revoked` / `@cert-authority` markers, aur CA-signed host
+//! certificates (`*-cert-v01@openssh.com`).
+//!
+//! Kai files ek saath load hoti hain (global + user); naye entries sirf user
+//! file me append hote hain, chahe to hashed.
+
+use crate::ssh_config::match_list;
+use crate::NetError;
+use base64::Engine;
+use ring::{hmac, rand::SecureRandom, signature};
+use sha2::Digest;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Host key check ka nateeja.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KnownHostStatus {
+    /// Key kisi entry se match hui.
+    Match,
+    /// Host certificate ek trusted `@cert-authority` ne sign kiya hai aur valid hai.
+    CertSigned,
+    /// Host ke liye same type ki doosri key recorded hai (MITM ya key rotation).
+    Mismatch,
+    /// Key (ya use sign karne wala CA) `@revoked` hai.
+    Revoked,
+    /// Certificate trusted CA ka hai lekin invalid (expired, principal nahi, bad signature...).
+    BadCertificate(String),
+    /// Host ke liye is type ki koi entry nahi.
+    NotFound,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    None,
+    Revoked,
+    CertAuthority,
+}
+
+#[derive(Debug, Clone)]
+enum Hosts {
+    Patterns(Vec<String>),
+    Hashed { salt: Vec<u8>, hash: Vec<u8> },
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    marker: Marker,
+    hosts: Hosts,
+    key_type: String,
+    key: Vec<u8>,
+}
+
+/// Ek ya zyada known_hosts files ki entries.
+#[derive(Debug, Clone, Default)]
+pub struct KnownHosts {
+    entries: Vec<Entry>,
+}
+
+impl KnownHosts {
+    /// Files order me load karo; jo file nahi hai wo skip (OpenSSH jaisa).
+    pub fn load<P: AsRef<Path>>(paths: &[P]) -> Result<Self, NetError> {
+        let mut out = Self::default();
+        for path in paths {
+            let path = path.as_ref();
+            match fs::read_to_string(path) {
+                Ok(text) => out.entries.extend(Self::parse(&text).entries),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(NetError::Invalid(format!("cannot read known_hosts {}: {e}", path.display()))),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Text parse karo; kharab lines chupchaap skip hoti hain.
+    pub fn parse(text: &str) -> Self {
+        let entries = text.lines().filter_map(parse_line).collect();
+        Self { entries }
+    }
+
+    /// OpenSSH ki default global files (`GlobalKnownHostsFile` ka default).
+    pub fn default_global_files() -> Vec<PathBuf> {
+        if cfg!(windows) {
+            let base = std::env::var_os("ProgramData").map(PathBuf::from).unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"));
+            vec![base.join("ssh").join("ssh_known_hosts")]
+        } else {
+            vec![PathBuf::from("/etc/ssh/ssh_known_hosts"), PathBuf::from("/etc/ssh/ssh_known_hosts2")]
+        }
+    }
+
+    /// Koi entry load nahi hui (files missing ya khali).
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// `host:port` ne `key_blob` (wire format) present kiya; kya hum use jaante hain?
+    pub fn check(&self, host: &str, port: u16, key_blob: &[u8]) -> KnownHostStatus {
+        let name = host_entry_name(host, port);
+        if let Some(cert) = Certificate::parse(key_blob) {
+            if self.is_revoked(&name, &cert.plain_key) || self.is_revoked(&name, &cert.signature_key) {
+                return KnownHostStatus::Revoked;
+            }
+            let trusted = self
+                .matching(&name, Marker::CertAuthority)
+                .any(|e| e.key == cert.signature_key);
+            if trusted {
+                return match cert.validate(host, now_secs()) {
+                    Ok(()) => KnownHostStatus::CertSigned,
+                    Err(why) => KnownHostStatus::BadCertificate(why),
+                };
+            }
+            // Koi CA trusted nahi: certificate ki andar wali plain key se check karo
+            return self.check_plain(&name, &cert.plain_key);
+        }
+        if self.is_revoked(&name, key_blob) {
+            return KnownHostStatus::Revoked;
+        }
+        self.check_plain(&name, key_blob)
+    }
+
+    fn check_plain(&self, name: &str, key_blob: &[u8]) -> KnownHostStatus {
+        let key_type = blob_key_type(key_blob).unwrap_or_default();
+        let mut same_type = false;
+        for e in self.matching(name, Marker::None) {
+            if e.key == key_blob {
+                return KnownHostStatus::Match;
+            }
+            same_type |= e.key_type == key_type;
+        }
+        if same_type { KnownHostStatus::Mismatch } else { KnownHostStatus::NotFound }
+    }
+
+    fn is_revoked(&self, name: &str, key_blob: &[u8]) -> bool {
+        self.matching(name, Marker::Revoked).any(|e| e.key == key_blob)
+    }
+
+    fn matching<'a>(&'a self, name: &'a str, marker: Marker) -> impl Iterator<Item = &'a Entry> + 'a {
+        self.entries.iter().filter(move |e| e.marker == marker && hosts_match(&e.hosts, name))
+    }
+
+    /// `path` me `host:port` ke liye nayi entry append karo (file/dir na ho to bana do).
+    /// `hashed` ho to hostname `HashKnownHosts` ki tarah HMAC-SHA1 se chhupaya jaata hai.
+    pub fn append(path: &Path, host: &str, port: u16, key_blob: &[u8], hashed: bool) -> Result<(), NetError> {
+        let key_type = blob_key_type(key_blob).ok_or_else(|| NetError::Invalid("malformed host key".into()))?;
+        let name = host_entry_name(host, port);
+        let hosts = if hashed { hash_host(&name)? } else { name };
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let line = format!("{hosts} {key_type} {}\n", b64.encode(key_blob));
+
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        // Pichli line newline pe khatam na ho to pehle ek newline
+        let needs_newline = fs::read(path).map(|b| !b.is_empty() && !b.ends_with(b"\n")).unwrap_or(false);
+        let mut f = OpenOptions::new().create(true).append(true).open(path)?;
+        if needs_newline {
+            f.write_all(b"\n")?;
+        }
+        f.write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Port 22 pe sirf host, warna `[host]:port`.
+fn host_entry_name(host: &str, port: u16) -> String {
+    if port == 22 { host.to_string() } else { format!("[{host}]:{port}") }
+}
+
+fn parse_line(line: &str) -> Option<Entry> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let mut fields = line.split_whitespace();
+    let mut first = fields.next()?;
+    let marker = match first {
+        "@revoked" => Marker::Revoked,
+        "@cert-authority" => Marker::CertAuthority,
+        m if m.starts_with('@') => return None,
+        _ => Marker::None,
+    };
+    if marker != Marker::None {
+        first = fields.next()?;
+    }
+    let key_type = fields.next()?.to_string();
+    let key = base64::engine::general_purpose::STANDARD.decode(fields.next()?).ok()?;
+    let hosts = match first.strip_prefix("|1|") {
+        Some(rest) => {
+            let (salt, hash) = rest.split_once('|')?;
+            let b64 = base64::engine::general_purpose::STANDARD;
+            Hosts::Hashed { salt: b64.decode(salt).ok()?, hash: b64.decode(hash).ok()? }
+        }
+        None => Hosts::Patterns(vec![first.to_string()]),
+    };
+    Some(Entry { marker, hosts, key_type, key })
+}
+
+fn hosts_match(hosts: &Hosts, name: &str) -> bool {
+    match hosts {
+        Hosts::Patterns(p) => match_list(p, name),
+        Hosts::Hashed { salt, hash } => {
+            let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, salt);
+            hmac::verify(&key, name.as_bytes(), hash).is_ok()
+        }
+    }
+}
+
+/// `|1|base64(salt)|base64(HMAC-SHA1(salt, name))`, random 20-byte salt.
+fn hash_host(name: &str) -> Result<String, NetError> {
+    let mut salt = [0u8; 20];
+    ring::rand::SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| NetError::Invalid("no randomness for known_hosts salt".into()))?;
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, &salt);
+    let tag = hmac::sign(&key, name.as_bytes());
+    let b64 = base64::engine::general_purpose::STANDARD;
+    Ok(format!("|1|{}|{}", b64.encode(salt), b64.encode(tag.as_ref())))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Key blob ka pehla string: algorithm name.
+pub(crate) fn blob_key_type(blob: &[u8]) -> Option<String> {
+    let mut r = Reader(blob);
+    r.string().and_then(|s| String::from_utf8(s.to_vec()).ok())
+}
+
+/// OpenSSH jaisa "visual host key" (`ssh-keygen -lv`): SHA256 digest pe
+/// drunken-bishop walk, 17x9 box me.
+pub(crate) fn randomart(key_blob: &[u8]) -> String {
+    const W: usize = 17;
+    const H: usize = 9;
+    const SYMBOLS: &[u8] = b" .o+=*BOX@%&#/^SE";
+    let (start, end) = (SYMBOLS.len() - 2, SYMBOLS.len() - 1);
+
+    let mut field = [[0usize; H]; W];
+    let (mut x, mut y) = (W / 2, H / 2);
+    for byte in sha2::Sha256::digest(key_blob) {
+        let mut input = byte;
+        for _ in 0..4 {
+            x = if input & 1 != 0 { (x + 1).min(W - 1) } else { x.saturating_sub(1) };
+            y = if input & 2 != 0 { (y + 1).min(H - 1) } else { y.saturating_sub(1) };
+            if field[x][y] < start - 1 {
+                field[x][y] += 1;
+            }
+            input >>= 2;
+        }
+    }
+    field[W / 2][H / 2] = start;
+    field[x][y] = end;
+
+    let border = |title: &str| {
+        let left = (W - title.len().min(W)) / 2;
+        format!("+{}{title}{}+", "-".repeat(left), "-".repeat(W.saturating_sub(left + title.len())))
+    };
+    let (kind, bits) = key_summary(key_blob);
+    let mut out = border(&format!("[{kind} {bits}]"));
+    for row in 0..H {
+        out.push_str("\n|");
+        out.extend((0..W).map(|col| SYMBOLS[field[col][row]] as char));
+        out.push('|');
+    }
+    out.push('\n');
+    out.push_str(&border("[SHA256]"));
+    out
+}
+
+/// `ssh-keygen` wala key type label aur size bits me, e.g. ("ED25519", 256).
+fn key_summary(key_blob: &[u8]) -> (String, usize) {
+    let (blob, cert) = match Certificate::parse(key_blob) {
+        Some(c) => (c.plain_key, "-CERT"),
+        None => (key_blob.to_vec(), ""),
+    };
+    let mut r = Reader(&blob);
+    let key_type = r.string().unwrap_or_default();
+    let mpint_bits = |b: Option<&[u8]>| {
+        let b = strip_mpint(b.unwrap_or_default());
+        b.first().map_or(0, |&hi| b.len() * 8 - hi.leading_zeros() as usize)
+    };
+    let (kind, bits) = match key_type {
+        b"ssh-ed25519" => ("ED25519", 256),
+        b"ssh-rsa" => {
+            r.string(); // e
+            ("RSA", mpint_bits(r.string()))
+        }
+        b"ssh-dss" => ("DSA", mpint_bits(r.string())),
+        b"ecdsa-sha2-nistp256" => ("ECDSA", 256),
+        b"ecdsa-sha2-nistp384" => ("ECDSA", 384),
+        b"ecdsa-sha2-nistp521" => ("ECDSA", 521),
+        _ => ("UNKNOWN", 0),
+    };
+    (format!("{kind}{cert}"), bits)
+}
+
+/// SSH wire format reader (RFC 4251 strings/uints).
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8).map(|b| u64::from_be_bytes(b.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Option<&'a [u8]> {
+        let n = self.u32()? as usize;
+        self.take(n)
+    }
+}
+
+fn put_string(out: &mut Vec<u8>, s: &[u8]) {
+    out.extend_from_slice(&(s.len() as u32).to_be_bytes());
+    out.extend_from_slice(s);
+}
+
+/// OpenSSH certificate (PROTOCOL.certkeys) ke zaroori fields.
+struct Certificate {
+    /// Certificate ki andar wali key, plain (non-cert) blob ke roop me.
+    plain_key: Vec<u8>,
+    cert_type: u32,
+    principals: Vec<String>,
+    valid_after: u64,
+    valid_before: u64,
+    critical_options: Vec<u8>,
+    signature_key: Vec<u8>,
+    signature: Vec<u8>,
+    /// Signature se pehle tak ke bytes; CA inhi pe sign karta hai.
+    signed: Vec<u8>,
+}
+
+const HOST_CERT: u32 = 2;
+
+impl Certificate {
+    fn parse(blob: &[u8]) -> Option<Self> {
+        let mut r = Reader(blob);
+        let cert_type_name = std::str::from_utf8(r.string()?).ok()?;
+        let plain_type = cert_type_name.strip_suffix("-cert-v01@openssh.com")?;
+        r.string()?; // nonce
+
+        // Key-specific public fields, plain blob banane ke liye
+        let mut plain_key = Vec::new();
+        put_string(&mut plain_key, plain_type.as_bytes());
+        let fields = match plain_type {
+            "ssh-ed25519" => 1,
+            "ssh-rsa" => 2,                                                            // e, n
+            t if t.starts_with("ecdsa-sha2-") => 2,                                    // curve, Q
+            "ssh-dss" => 4,                                                            // p, q, g, y
+            _ => return None,
+        };
+        for _ in 0..fields {
+            put_string(&mut plain_key, r.string()?);
+        }
+
+        r.u64()?; // serial
+        let cert_type = r.u32()?;
+        r.string()?; // key id
+        let mut principals = Vec::new();
+        let mut pr = Reader(r.string()?);
+        while !pr.0.is_empty() {
+            principals.push(String::from_utf8(pr.string()?.to_vec()).ok()?);
+        }
+        let valid_after = r.u64()?;
+        let valid_before = r.u64()?;
+        let critical_options = r.string()?.to_vec();
+        r.string()?; // extensions
+        r.string()?; // reserved
+        let signature_key = r.string()?.to_vec();
+        let signed = blob[..blob.len() - r.0.len()].to_vec();
+        let signature = r.string()?.to_vec();
+        Some(Self { plain_key, cert_type, principals, valid_after, valid_before, critical_options, signature_key, signature, signed })
+    }
+
+    /// OpenSSH ke host certificate rules: host type, principal match (khali list
+    /// = koi bhi host), validity window, koi critical option nahi, CA signature.
+    fn validate(&self, host: &str, now: u64) -> Result<(), String> {
+        if self.cert_type != HOST_CERT {
+            return Err("not a host certificate".into());
+        }
+        if !self.principals.is_empty() && !self.principals.iter().any(|p| p.eq_ignore_ascii_case(host)) {
+            return Err(format!("certificate is not valid for {host} (principals: {})", self.principals.join(", ")));
+        }
+        if now < self.valid_after || now >= self.valid_before {
+            return Err("certificate is expired or not yet valid".into());
+        }
+        if !self.critical_options.is_empty() {
+            return Err("certificate has unsupported critical options".into());
+        }
+        verify_signature(&self.signature_key, &self.signature, &self.signed)
+    }
+}
+
+/// CA key (`ca_blob`) se `data` pe bani SSH signature check karo.
+fn verify_signature(ca_blob: &[u8], sig_blob: &[u8], data: &[u8]) -> Result<(), String> {
+    let mut ca = Reader(ca_blob);
+    let ca_type = ca.string().ok_or("malformed CA key")?;
+    let mut sig = Reader(sig_blob);
+    let sig_type = sig.string().ok_or("malformed signature")?;
+    let sig_bytes = sig.string().ok_or("malformed signature")?;
+
+    let ok = match (ca_type, sig_type) {
+        (b"ssh-ed25519", b"ssh-ed25519") => {
+            let pk = ca.string().ok_or("malformed CA key")?;
+            signature::UnparsedPublicKey::new(&signature::ED25519, pk).verify(data, sig_bytes).is_ok()
+        }
+        (b"ssh-rsa", b"rsa-sha2-256" | b"rsa-sha2-512") => {
+            let e = ca.string().ok_or("malformed CA key")?;
+            let n = ca.string().ok_or("malformed CA key")?;
+            let alg = if sig_type == b"rsa-sha2-256" {
+                &signature::RSA_PKCS1_2048_8192_SHA256
+            } else {
+                &signature::RSA_PKCS1_2048_8192_SHA512
+            };
+            signature::RsaPublicKeyComponents { n: strip_mpint(n), e: strip_mpint(e) }.verify(alg, data, sig_bytes).is_ok()
+        }
+        (b"ecdsa-sha2-nistp256", b"ecdsa-sha2-nistp256") | (b"ecdsa-sha2-nistp384", b"ecdsa-sha2-nistp384") => {
+            ca.string().ok_or("malformed CA key")?; // curve name
+            let q = ca.string().ok_or("malformed CA key")?;
+            let (alg, width) = if ca_type == b"ecdsa-sha2-nistp256" {
+                (&signature::ECDSA_P256_SHA256_FIXED, 32)
+            } else {
+                (&signature::ECDSA_P384_SHA384_FIXED, 48)
+            };
+            // SSH ECDSA signature = mpint r, mpint s; ring ko fixed-width r||s chahiye
+            let mut rs = Reader(sig_bytes);
+            let (r, s) = (rs.string().ok_or("malformed signature")?, rs.string().ok_or("malformed signature")?);
+            let fixed = [fixed_width(strip_mpint(r), width)?, fixed_width(strip_mpint(s), width)?].concat();
+            signature::UnparsedPublicKey::new(alg, q).verify(data, &fixed).is_ok()
+        }
+        (ca_type, sig_type) => {
+            return Err(format!(
+                "unsupported CA signature {} with {} key",
+                String::from_utf8_lossy(sig_type),
+                String::from_utf8_lossy(ca_type)
+            ));
+        }
+    };
+    if ok { Ok(()) } else { Err("certificate signature does not verify".into()) }
+}
+
+/// mpint ka leading sign byte (0x00) hatao.
+fn strip_mpint(b: &[u8]) -> &[u8] {
+    let zeros = b.iter().take_while(|&&x| x == 0).count();
+    &b[zeros..]
+}
+
+fn fixed_width(b: &[u8], width: usize) -> Result<Vec<u8>, String> {
+    if b.len() > width {
+        return Err("malformed ECDSA signature".into());
+    }
+    let mut out = vec![0u8; width - b.len()];
+    out.extend_from_slice(b);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ssh-keygen se bane vectors: host key, ed25519/ecdsa/rsa CAs, aur unke signed host certs
+    // (`-h -n sftp.example.com[,10.0.0.5] -V 20200101:20990101`).
+    const HOST: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIPBwl1dESN2zfRtJOwvPesLHK0sjMnSyZy1mI9WI3vhL";
+    const CA_ED: &str = "AAAAC3NzaC1lZDI1NTE5AAAAILdL6QQ3d9ryjEhMUIkeWkC55a0VGZYjk5qzg9WTYRRl";
+    const CA_EC: &str = "AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBKY6rkHQB3XDO9gUP+V0MpujcGZiviULTnzKRwihl8bLbG6u/0yWZo7PIrpXfElc9N8Wih0Gd+FeaPQAhTmr92o=";
+    const CA_RSA: &str = "AAAAB3NzaC1yc2EAAAADAQABAAABAQDhu4idkBpKQ8oYSVs2Mjgz0qbfsLBNDtxfkVqxEUKwnp00L4vUxESTEBuNb9K8orNShYB33UAMI7uhDWvE2BDbDdTFzJoF8c973o9M8ZFWYbDaZF2yDQkEBX+Yf4eDs/OTJzaLB4tB5GB6dbPjmdy+sAopuEYmhXUcFEg6GyFPrVXEo6rDj3PPmkf177pnjP61dTYk2WhZqVu1WcJNH6usZZkcJsWNnq4zEMrhPc6kPwB5oO8EUmGMaVyX2K6MNxGrQtWGpEhQJdNIMa1Vf87BrKLRGmvQII1i6rNTr9C9zwpbunN4gNWdFVvD6H44rRoW6icj4TImLmR3I56WDcVb";
+    const CERT_ED: &str = "AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIA7gTc8ouHgLAnI4TJbQZ+cK5/1kTzuLwDAv7r2JNXvNAAAAIPBwl1dESN2zfRtJOwvPesLHK0sjMnSyZy1mI9WI3vhLAAAAAAAAAAAAAAACAAAACXRlc3QtaG9zdAAAACAAAAAQc2Z0cC5leGFtcGxlLmNvbQAAAAgxMC4wLjAuNQAAAABeC+EAAAAAAPKlI4AAAAAAAAAAAAAAAAAAAAAzAAAAC3NzaC1lZDI1NTE5AAAAILdL6QQ3d9ryjEhMUIkeWkC55a0VGZYjk5qzg9WTYRRlAAAAUwAAAAtzc2gtZWQyNTUxOQAAAEBJkMua//H6ADVDciUrYGDguJfPJBuHIy6mj29do4dwnQCLderMK57C5Dekt6Der/G9cIpv/Tpk4+iWV0iv/QoK";
+    const CERT_EC: &str = "AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIFL+1QMw2On8JmyV7Kn5RUrvDFGDTSxqW7Saq2OFv4OWAAAAIPBwl1dESN2zfRtJOwvPesLHK0sjMnSyZy1mI9WI3vhLAAAAAAAAAAcAAAACAAAACWVjLXNpZ25lZAAAABQAAAAQc2Z0cC5leGFtcGxlLmNvbQAAAABeC+EAAAAAAPKlI4AAAAAAAAAAAAAAAAAAAABoAAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBKY6rkHQB3XDO9gUP+V0MpujcGZiviULTnzKRwihl8bLbG6u/0yWZo7PIrpXfElc9N8Wih0Gd+FeaPQAhTmr92oAAABkAAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAABJAAAAIBcJvzmI2lApxwKNps8g0ArdbZ79PdYqzZHF/bRwG3OjAAAAIQD24q7rBeWqzeFt2bEnZSyeftfH6838vUh1yJiSGBKSLw==";
+    const CERT_RSA: &str = "AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIFQ85E8p+erRwK7Kuthv+SwyT/f7fbC1VmN6h3w4deLGAAAAIPBwl1dESN2zfRtJOwvPesLHK0sjMnSyZy1mI9WI3vhLAAAAAAAAAAAAAAACAAAACnJzYS1zaWduZWQAAAAUAAAAEHNmdHAuZXhhbXBsZS5jb20AAAAAXgvhAAAAAADypSOAAAAAAAAAAAAAAAAAAAABFwAAAAdzc2gtcnNhAAAAAwEAAQAAAQEA4buInZAaSkPKGElbNjI4M9Km37CwTQ7cX5FasRFCsJ6dNC+L1MREkxAbjW/SvKKzUoWAd91ADCO7oQ1rxNgQ2w3UxcyaBfHPe96PTPGRVmGw2mRdsg0JBAV/mH+Hg7Pzkyc2iweLQeRgenWz45ncvrAKKbhGJoV1HBRIOhshT61VxKOqw49zz5pH9e+6Z4z+tXU2JNloWalbtVnCTR+rrGWZHCbFjZ6uMxDK4T3OpD8AeaDvBFJhjGlcl9iujDcRq0LVhqRIUCXTSDGtVX/Owayi0Rpr0CCNYuqzU6/Qvc8KW7pzeIDVnRVbw+h+OK0aFuonI+EyJi5kdyOelg3FWwAAARQAAAAMcnNhLXNoYTItNTEyAAABAMHkMNalK/t8d3hHUbeipiJGPetOpdMzmj/Mdkm+7oW5jjvN2nrUhKcpWuL7C+pdtEJ+Kl96YAh+BLho7Xg8iYOQ6zpilsf4wbXyNWOZlBsxZRODrrV+Qg3tdFUwUzXIbZE7IpxqmT5rPN/qBDnECRw6UwnjtjJhhU2PLlt7762hbE8bNUTVFXiWuJQ+OGCcYphnYSQ+ZliHZfivOj+nmi2upkznERkWN/qqz9B2v0M4fNsWt3cGCcDcfdvhn1YsfhzJf9kReXc39v91sZKPnc8cCmgSIhLuHJE8jj1X73OFUMmFZdDE32ibr44jCCJEuvyu8gw51cBjO+qSalW3sOE=";
+    /// `-V 20000101:20010101`
+    const CERT_EXPIRED: &str = "AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIC6hcpTPeqdDQlnen4TmltX+cpIy/Lvve9qBZPwWgX1YAAAAIPBwl1dESN2zfRtJOwvPesLHK0sjMnSyZy1mI9WI3vhLAAAAAAAAAAAAAAACAAAAB2V4cGlyZWQAAAAUAAAAEHNmdHAuZXhhbXBsZS5jb20AAAAAOG1DgAAAAAA6T8iAAAAAAAAAAAAAAAAAAAAAMwAAAAtzc2gtZWQyNTUxOQAAACC3S+kEN3fa8oxITFCJHlpAueWtFRmWI5Oas4PVk2EUZQAAAFMAAAALc3NoLWVkMjU1MTkAAABAGT+JGouiD+r5rzkUfBYWPtzF3780cK22zEqlnYXWn741kB8sB0R6HTjqHyAEj8hA/80oGPwkd4zfTo+kQlsBBA==";
+
+    fn blob(b64: &str) -> Vec<u8> {
+        base64::engine::general_purpose::STANDARD.decode(b64).unwrap()
+    }
+
+    #[test]
+    fn plain_hashed_and_revoked_entries() {
+        let other = "AAAAC3NzaC1lZDI1NTE5AAAAID8zBXfx8nu6m6sMFXqVzTLTQ3Wb7Wdmr92nzL4EIDpo";
+        let kh = KnownHosts::parse(&format!(
+            "# comment\n\
+             web,!web.bad ssh-ed25519 {HOST}\n\
+             |1|tK22sd+j+64OWdd+9ux71kVMogA=|9R9eeJy8gLtAzKTUPmijv13q7Q0= ssh-ed25519 {HOST}\n\
+             changed ssh-ed25519 {other}\n\
+             @revoked * ssh-ed25519 {other}\n\
+             @bogus x ssh-ed25519 {HOST}\n"
+        ));
+        let host = blob(HOST);
+        assert_eq!(kh.check("web", 22, &host), KnownHostStatus::Match);
+        assert_eq!(kh.check("web.bad", 22, &host), KnownHostStatus::NotFound);
+        // Hashed line `ssh-keygen -H` se: [sftp.example.com]:2222
+        assert_eq!(kh.check("sftp.example.com", 2222, &host), KnownHostStatus::Match);
+        assert_eq!(kh.check("sftp.example.com", 22, &host), KnownHostStatus::NotFound);
+        assert_eq!(kh.check("changed", 22, &host), KnownHostStatus::Mismatch);
+        assert_eq!(kh.check("changed", 22, &blob(other)), KnownHostStatus::Revoked);
+    }
+
+    #[test]
+    fn append_writes_hashed_lines_we_can_match() {
+        let path = std::env::temp_dir().join(format!("sftp-net-kh-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        fs::write(&path, "old ssh-ed25519 AAAA").unwrap(); // bina trailing newline
+        KnownHosts::append(&path, "new.example.com", 2200, &blob(HOST), true).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let last = text.lines().last().unwrap();
+        assert!(last.starts_with("|1|") && !text.contains("new.example.com"), "{text}");
+        let kh = KnownHosts::load(&[&path, &path.with_extension("missing")]).unwrap();
+        assert_eq!(kh.check("new.example.com", 2200, &blob(HOST)), KnownHostStatus::Match);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn certificates_signed_by_trusted_ca() {
+        let kh = KnownHosts::parse(&format!(
+            "@cert-authority *.example.com ssh-ed25519 {CA_ED}\n\
+             @cert-authority *.example.com ecdsa-sha2-nistp256 {CA_EC}\n\
+             @cert-authority *.example.com ssh-rsa {CA_RSA}\n"
+        ));
+        for cert in [CERT_ED, CERT_EC, CERT_RSA] {
+            assert_eq!(kh.check("sftp.example.com", 22, &blob(cert)), KnownHostStatus::CertSigned);
+        }
+        // Principal list me nahi / CA is host ke liye trusted nahi
+        assert!(matches!(kh.check("db.example.com", 22, &blob(CERT_ED)), KnownHostStatus::BadCertificate(_)));
+        assert_eq!(kh.check("10.0.0.5", 22, &blob(CERT_ED)), KnownHostStatus::NotFound);
+        assert!(matches!(kh.check("sftp.example.com", 22, &blob(CERT_EXPIRED)), KnownHostStatus::BadCertificate(_)));
+
+        // Signature ka ek byte badlo
+        let mut tampered = blob(CERT_ED);
+        let n = tampered.len();
+        tampered[n - 1] ^= 1;
+        assert!(matches!(kh.check("sftp.example.com", 22, &tampered), KnownHostStatus::BadCertificate(_)));
+
+        // Revoked CA, aur plain key fallback jab CA trusted nahi
+        let revoked = KnownHosts::parse(&format!(
+            "@cert-authority * ssh-ed25519 {CA_ED}\n@revoked * ssh-ed25519 {CA_ED}\n"
+        ));
+        assert_eq!(revoked.check("sftp.example.com", 22, &blob(CERT_ED)), KnownHostStatus::Revoked);
+        let plain = KnownHosts::parse(&format!("sftp.example.com ssh-ed25519 {HOST}\n"));
+        assert_eq!(plain.check("sftp.example.com", 22, &blob(CERT_ED)), KnownHostStatus::Match);
+    }
+
+    #[test]
+    fn randomart_matches_ssh_keygen() {
+        // `ssh-keygen -lvf` output for the same keys
+        let ed = "\
++--[ED25519 256]--+
+|            . .=*|
+|           . ...o|
+|   E  . o   ..   |
+|    =. ..+. .+ . |
+|   o.. .Soooo o  |
+|     ... +.+*    |
+|    +  ....*=+   |
+|   o .o.. *=*    |
+|      oo ++=..   |
++----[SHA256]-----+";
+        assert_eq!(randomart(&blob(HOST)), ed);
+        let rsa = randomart(&blob(CA_RSA));
+        assert!(rsa.starts_with("+---[RSA 2048]----+\n|     .oo.o=B.    |\n"), "{rsa}");
+    }
+}
@@ -0,0 +1,587 @@
+//! Bulk data ke liye chhota SFTP v3 client jo ek hi channel pe kai READ/WRITE
+//! requests ek saath in flight rakhta hai.
+//!
+//! libssh2 ka `sftp_read`/`sftp_write` request size (~30 KB) aur read-ahead
+//! khud tay karta hai, caller kuch control nahi kar sakta. Yahan `chunk` har
+//! request ka size hai aur `depth` kitni requests bina reply ke chal sakti hain,
+//! isliye high-latency link pe throughput RTT se bandha nahi rehta. Wahi channel
+//! `posix-rename@openssh.com` bhi bhejta hai, jo ssh2 wrap nahi karta. Open/stat/
+//! rename jaise baaki kaam ab bhi ssh2 ke `Sftp` se hote hain.
+
+use crate::{path_err, read_full, CancelToken, NetError};
+use ssh2::ErrorCode;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+const FXP_INIT: u8 = 1;
+const FXP_VERSION: u8 = 2;
+const FXP_OPEN: u8 = 3;
+const FXP_CLOSE: u8 = 4;
+const FXP_READ: u8 = 5;
+const FXP_WRITE: u8 = 6;
+const FXP_EXTENDED: u8 = 200;
+const FXP_STATUS: u8 = 101;
+const FXP_HANDLE: u8 = 102;
+const FXP_DATA: u8 = 103;
+
+pub(crate) const FXF_READ: u32 = 0x01;
+pub(crate) const FXF_WRITE: u32 = 0x02;
+
+const FX_OK: u32 = 0;
+const FX_EOF: u32 = 1;
+const FX_OP_UNSUPPORTED: u32 = 8;
+
+const POSIX_RENAME: &str = "posix-rename@openssh.com";
+
+/// Ek request ka sabse bada data size. OpenSSH 256 KiB se bade packets drop
+/// karta hai; bade reads ko server khud chhota karke bhejta hai.
+pub(crate) const MAX_CHUNK: usize = 255 * 1024;
+
+/// Reply packet ki upper limit; isse bada length header matlab stream bigad gaya.
+const MAX_PACKET: usize = 2 * MAX_CHUNK;
+
+/// SFTP v3 connection ek byte stream pe (asli me `ssh2::Channel`, tests me fake server).
+pub(crate) struct Pipe<T> {
+    io: T,
+    next_id: u32,
+    /// Server ke VERSION reply me advertise kiye extensions.
+    extensions: Vec<String>,
+    /// Stream pe koi reply adha padha reh gaya: dobara use nahi ho sakta.
+    broken: bool,
+}
+
+/// Server ka reply, jitna hume chahiye utna.
+enum Reply {
+    Status(u32),
+    Handle(Vec<u8>),
+    Data(Vec<u8>),
+}
+
+impl<T: Read + Write> Pipe<T> {
+    /// `SSH_FXP_INIT` bhej ke version 3 negotiate karo.
+    pub(crate) fn init(io: T) -> Result<Self, NetError> {
+        let mut pipe = Self { io, next_id: 0, extensions: Vec::new(), broken: false };
+        pipe.send(Packet::new(FXP_INIT).u32(3))?;
+        let body = pipe.read_packet()?;
+        let mut r = Reader(&body[1..]);
+        if body[0] != FXP_VERSION || r.u32().is_none() {
+            return Err(pipe.protocol("expected SSH_FXP_VERSION"));
+        }
+        // Extension pairs: name, data
+        while let (Some(name), Some(_)) = (r.bytes(), r.bytes()) {
+            pipe.extensions.push(String::from_utf8_lossy(name).into_owned());
+        }
+        Ok(pipe)
+    }
+
+    pub(crate) fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// File kholo; sirf existing files ke liye (create/truncate ssh2 wala open karta hai).
+    pub(crate) fn open(&mut self, path: &Path, pflags: u32) -> Result<OpenFile, NetError> {
+        let id = self.send_request(Packet::new(FXP_OPEN).bytes(&crate::remote_bytes(path)).u32(pflags).u32(0))?;
+        match self.recv_for(id)? {
+            Reply::Handle(handle) => Ok(OpenFile { handle, path: path.to_path_buf() }),
+            Reply::Status(code) => Err(status_err(code, path)),
+            Reply::Data(_) => Err(self.protocol("unexpected SSH_FXP_DATA for open")),
+        }
+    }
+
+    pub(crate) fn close(&mut self, file: OpenFile) -> Result<(), NetError> {
+        let id = self.send_request(Packet::new(FXP_CLOSE).bytes(&file.handle))?;
+        match self.recv_for(id)? {
+            Reply::Status(FX_OK) => Ok(()),
+            Reply::Status(code) => Err(status_err(code, &file.path)),
+            _ => Err(self.protocol("unexpected reply to close")),
+        }
+    }
+
+    /// `posix-rename@openssh.com`: `to` ho to use atomically replace karta hai.
+    /// Server ne extension advertise na kiya ho to bina bheje FX_OP_UNSUPPORTED.
+    pub(crate) fn posix_rename(&mut self, from: &Path, to: &Path) -> Result<(), NetError> {
+        if !self.extensions.iter().any(|e| e == POSIX_RENAME) {
+            return Err(status_err(FX_OP_UNSUPPORTED, from));
+        }
+        let (src, dst) = (crate::remote_bytes(from), crate::remote_bytes(to));
+        let id = self.send_request(Packet::new(FXP_EXTENDED).bytes(POSIX_RENAME.as_bytes()).bytes(&src).bytes(&dst))?;
+        match self.recv_for(id)? {
+            Reply::Status(FX_OK) => Ok(()),
+            Reply::Status(code) => Err(status_err(code, from)),
+            _ => Err(self.protocol("unexpected reply to posix-rename")),
+        }
+    }
+
+    /// `path` kholo, `op` chalao aur handle band karo; `op` ka error close ke error se pehle.
+    pub(crate) fn with_file<R>(&mut self, path: &Path, pflags: u32, op: impl FnOnce(&mut Self, &OpenFile) -> Result<R, NetError>) -> Result<R, NetError> {
+        let file = self.open(path, pflags)?;
+        let res = op(self, &file);
+        if self.broken {
+            return res;
+        }
+        let closed = self.close(file);
+        let value = res?;
+        closed.map(|()| value)
+    }
+
+    /// `[start, end)` (ya EOF tak) padh ke `dst` me likho; likhe gaye bytes lautao.
+    ///
+    /// `depth` READs hamesha in flight rehte hain. Server chhota DATA bheje to
+    /// baaki hissa turant dobara maanga jaata hai; out-of-order replies offset
+    /// ke hisaab se jode jaate hain. Error ya cancel pe bache replies padh ke hi
+    /// lautte hain, taaki stream agle transfer ke liye sahi rahe.
+    pub(crate) fn download<W: Write>(&mut self, file: &OpenFile, start: u64, end: Option<u64>, dst: &mut W, window: Window, cancel: &CancelToken) -> Result<u64, NetError> {
+        let mut inflight: HashMap<u32, (u64, u64)> = HashMap::new();
+        let mut ready: BTreeMap<u64, Vec<u8>> = BTreeMap::new();
+        let (mut next, mut pos, mut limit) = (start, start, end);
+        let mut failure: Option<NetError> = None;
+        let handle = file.handle.as_slice();
+        loop {
+            while failure.is_none() && inflight.len() < window.depth && limit.is_none_or(|l| next < l) {
+                let len = limit.map_or(window.chunk, |l| window.chunk.min(l - next));
+                let id = self.send_read(handle, next, len)?;
+                inflight.insert(id, (next, len));
+                next += len;
+            }
+            if inflight.is_empty() {
+                break;
+            }
+            let (id, reply) = self.recv()?;
+            let Some((off, len)) = inflight.remove(&id) else {
+                return Err(self.protocol("reply for unknown request"));
+            };
+            match reply {
+                Reply::Data(data) if !data.is_empty() && data.len() as u64 <= len => {
+                    let got = data.len() as u64;
+                    if got < len && failure.is_none() {
+                        let id = self.send_read(handle, off + got, len - got)?;
+                        inflight.insert(id, (off + got, len - got));
+                    }
+                    ready.insert(off, data);
+                }
+                // Khaali DATA ya EOF: file yahin khatam hai, isse aage maangna band
+                Reply::Data(_) | Reply::Status(FX_EOF) => limit = Some(limit.map_or(off, |l| l.min(off))),
+                Reply::Status(code) => {
+                    failure.get_or_insert_with(|| status_err(code, &file.path));
+                }
+                Reply::Handle(_) => return Err(self.protocol("unexpected SSH_FXP_HANDLE for read")),
+            }
+            while failure.is_none()
+                && let Some(data) = ready.remove(&pos)
+            {
+                match dst.write_all(&data) {
+                    Ok(()) => pos += data.len() as u64,
+                    Err(e) => failure = Some(e.into()),
+                }
+            }
+            if failure.is_none() && cancel.is_cancelled() {
+                failure = Some(NetError::Cancelled);
+            }
+        }
+        failure.map_or(Ok(pos - start), Err)
+    }
+
+    /// `src` ko `start` offset se EOF tak likho; likhe gaye bytes lautao.
+    ///
+    /// `depth` WRITEs bina ack ke chalte hain; har ack ka status check hota hai.
+    pub(crate) fn upload<R: Read>(&mut self, file: &OpenFile, start: u64, src: &mut R, window: Window, cancel: &CancelToken) -> Result<u64, NetError> {
+        let mut buf = vec![0u8; window.chunk as usize];
+        let mut inflight = 0usize;
+        let (mut off, mut done) = (start, false);
+        let mut failure: Option<NetError> = None;
+        loop {
+            while !done && failure.is_none() && inflight < window.depth {
+                if cancel.is_cancelled() {
+                    failure = Some(NetError::Cancelled);
+                    break;
+                }
+                match read_full(src, &mut buf) {
+                    Ok(0) => done = true,
+                    Ok(n) => {
+                        self.send_request(Packet::new(FXP_WRITE).bytes(&file.handle).u64(off).bytes(&buf[..n]))?;
+                        inflight += 1;
+                        off += n as u64;
+                    }
+                    Err(e) => failure = Some(e.into()),
+                }
+            }
+            if inflight == 0 {
+                break;
+            }
+            match self.recv()? {
+                (_, Reply::Status(FX_OK)) => {}
+                (_, Reply::Status(code)) => {
+                    failure.get_or_insert_with(|| status_err(code, &file.path));
+                }
+                _ => return Err(self.protocol("unexpected reply to write")),
+            }
+            inflight -= 1;
+        }
+        failure.map_or(Ok(off - start), Err)
+    }
+
+    fn send_read(&mut self, handle: &[u8], off: u64, len: u64) -> Result<u32, NetError> {
+        self.send_request(Packet::new(FXP_READ).bytes(handle).u64(off).u32(len as u32))
+    }
+
+    /// Packet pe naya request id laga ke bhejo.
+    fn send_request(&mut self, packet: Packet) -> Result<u32, NetError> {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        let mut packet = packet;
+        packet.0.splice(5..5, id.to_be_bytes());
+        self.send(packet)?;
+        Ok(id)
+    }
+
+    fn send(&mut self, packet: Packet) -> Result<(), NetError> {
+        let mut buf = packet.0;
+        let len = (buf.len() - 4) as u32;
+        buf[..4].copy_from_slice(&len.to_be_bytes());
+        // `flush` nahi: ssh2 `Channel::flush` incoming data discard kar deta hai
+        self.io.write_all(&buf).map_err(|e| self.io_err(e))
+    }
+
+    /// Sirf ek request chal rahi ho tab: uska reply lo.
+    fn recv_for(&mut self, id: u32) -> Result<Reply, NetError> {
+        match self.recv()? {
+            (got, reply) if got == id => Ok(reply),
+            _ => Err(self.protocol("reply for unknown request")),
+        }
+    }
+
+    fn recv(&mut self) -> Result<(u32, Reply), NetError> {
+        let body = self.read_packet()?;
+        let mut r = Reader(&body[1..]);
+        let id = r.u32();
+        let reply = match body[0] {
+            FXP_STATUS => r.u32().map(Reply::Status),
+            FXP_HANDLE => r.bytes().map(|h| Reply::Handle(h.to_vec())),
+            FXP_DATA => r.bytes().map(|d| Reply::Data(d.to_vec())),
+            _ => None,
+        };
+        match (id, reply) {
+            (Some(id), Some(reply)) => Ok((id, reply)),
+            _ => Err(self.protocol("malformed reply")),
+        }
+    }
+
+    /// Ek poora packet (type + payload) padho.
+    fn read_packet(&mut self) -> Result<Vec<u8>, NetError> {
+        let mut len = [0u8; 4];
+        self.io.read_exact(&mut len).map_err(|e| self.io_err(e))?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len == 0 || len > MAX_PACKET {
+            return Err(self.protocol("bad packet length"));
+        }
+        let mut body = vec![0u8; len];
+        self.io.read_exact(&mut body).map_err(|e| self.io_err(e))?;
+        Ok(body)
+    }
+
+    fn io_err(&mut self, err: io::Error) -> NetError {
+        self.broken = true;
+        err.into()
+    }
+
+    fn protocol(&mut self, what: &str) -> NetError {
+        self.broken = true;
+        NetError::Tcp(io::Error::new(io::ErrorKind::InvalidData, format!("sftp: {what}")))
+    }
+}
+
+/// Server pe khula hua file handle, errors ke liye path ke saath.
+pub(crate) struct OpenFile {
+    handle: Vec<u8>,
+    path: PathBuf,
+}
+
+/// Ek transfer ka pipelining: request size aur kitni requests in flight.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Window {
+    pub(crate) chunk: u64,
+    pub(crate) depth: usize,
+}
+
+impl Window {
+    pub(crate) fn new(chunk: usize, depth: usize) -> Self {
+        Self { chunk: chunk.clamp(1, MAX_CHUNK) as u64, depth: depth.max(1) }
+    }
+}
+
+/// SFTP status ko baaki code jaisa hi `path_err` se NetError banao.
+fn status_err(code: u32, path: &Path) -> NetError {
+    let msg = match code {
+        2 => "no such file",
+        3 => "permission denied",
+        4 => "failure",
+        5 => "bad message",
+        8 => "operation unsupported",
+        _ => "sftp request failed",
+    };
+    path_err(ssh2::Error::new(ErrorCode::SFTP(code as i32), msg), path)
+}
+
+/// Outgoing packet; pehle 4 bytes length ke, `send` bharta hai.
+struct Packet(Vec<u8>);
+
+impl Packet {
+    fn new(kind: u8) -> Self {
+        Self(vec![0, 0, 0, 0, kind])
+    }
+
+    fn u32(mut self, v: u32) -> Self {
+        self.0.extend_from_slice(&v.to_be_bytes());
+        self
+    }
+
+    fn u64(mut self, v: u64) -> Self {
+        self.0.extend_from_slice(&v.to_be_bytes());
+        self
+    }
+
+    fn bytes(self, v: &[u8]) -> Self {
+        let mut p = self.u32(v.len() as u32);
+        p.0.extend_from_slice(v);
+        p
+    }
+}
+
+/// Incoming payload ka cursor; chhota pade to `None`.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+    }
+
+    #[cfg(test)]
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8).map(|b| u64::from_be_bytes(b.try_into().unwrap()))
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let n = self.u32()? as usize;
+        self.take(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// In-memory SFTP server: har request likhte hi uska reply queue ho jaata hai.
+    /// `peak` batata hai ek saath kitne replies client ne abhi tak nahi padhe the,
+    /// yaani kitni requests in flight thi.
+    #[derive(Default)]
+    struct FakeServer {
+        files: HashMap<Vec<u8>, Vec<u8>>,
+        handles: HashMap<Vec<u8>, Vec<u8>>,
+        inbuf: Vec<u8>,
+        replies: VecDeque<Vec<u8>>,
+        current: VecDeque<u8>,
+        peak: usize,
+        /// READ/WRITE requests ke data sizes.
+        sizes: Vec<u64>,
+        /// DATA replies isse bade nahi hote (server-side short reads).
+        max_read: Option<usize>,
+        /// Queue ke replies ulte order me do.
+        reorder: bool,
+        /// Is offset ya aage ke WRITEs FX_FAILURE dete hain.
+        fail_writes_from: Option<u64>,
+        /// VERSION me advertise hone wale extensions.
+        extensions: Vec<&'static str>,
+        /// Kitne SSH_FXP_EXTENDED requests aaye.
+        extended: usize,
+    }
+
+    impl FakeServer {
+        fn handle(&mut self, body: &[u8]) -> Packet {
+            let mut r = Reader(&body[1..]);
+            if body[0] == FXP_INIT {
+                return self.extensions.iter().fold(Packet::new(FXP_VERSION).u32(3), |p, name| p.bytes(name.as_bytes()).bytes(b"1"));
+            }
+            let id = r.u32().unwrap();
+            let status = |code| Packet::new(FXP_STATUS).u32(id).u32(code).bytes(b"").bytes(b"");
+            match body[0] {
+                FXP_OPEN => {
+                    let path = r.bytes().unwrap().to_vec();
+                    if !self.files.contains_key(&path) {
+                        return status(2);
+                    }
+                    let handle = format!("h{}", self.handles.len()).into_bytes();
+                    self.handles.insert(handle.clone(), path);
+                    Packet::new(FXP_HANDLE).u32(id).bytes(&handle)
+                }
+                FXP_CLOSE => status(FX_OK),
+                FXP_READ => {
+                    let file = &self.files[&self.handles[r.bytes().unwrap()]];
+                    let (off, len) = (r.u64().unwrap() as usize, r.u32().unwrap() as usize);
+                    self.sizes.push(len as u64);
+                    if off >= file.len() {
+                        return status(FX_EOF);
+                    }
+                    let len = len.min(self.max_read.unwrap_or(usize::MAX));
+                    Packet::new(FXP_DATA).u32(id).bytes(&file[off..(off + len).min(file.len())])
+                }
+                FXP_WRITE => {
+                    let path = self.handles[r.bytes().unwrap()].clone();
+                    let (off, data) = (r.u64().unwrap(), r.bytes().unwrap());
+                    self.sizes.push(data.len() as u64);
+                    if self.fail_writes_from.is_some_and(|f| off >= f) {
+                        return status(4);
+                    }
+                    let file = self.files.get_mut(&path).unwrap();
+                    let end = off as usize + data.len();
+                    if file.len() < end {
+                        file.resize(end, 0);
+                    }
+                    file[off as usize..end].copy_from_slice(data);
+                    status(FX_OK)
+                }
+                FXP_EXTENDED => {
+                    self.extended += 1;
+                    let (name, from, to) = (r.bytes().unwrap(), r.bytes().unwrap().to_vec(), r.bytes().unwrap().to_vec());
+                    match self.files.remove(&from) {
+                        Some(data) if name == POSIX_RENAME.as_bytes() => {
+                            self.files.insert(to, data);
+                            status(FX_OK)
+                        }
+                        _ => status(2),
+                    }
+                }
+                _ => status(8),
+            }
+        }
+    }
+
+    impl Write for FakeServer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.inbuf.extend_from_slice(buf);
+            while self.inbuf.len() >= 4 {
+                let len = u32::from_be_bytes(self.inbuf[..4].try_into().unwrap()) as usize;
+                if self.inbuf.len() < 4 + len {
+                    break;
+                }
+                let body: Vec<u8> = self.inbuf.drain(..4 + len).skip(4).collect();
+                let mut reply = self.handle(&body).0;
+                let n = (reply.len() - 4) as u32;
+                reply[..4].copy_from_slice(&n.to_be_bytes());
+                self.replies.push_back(reply);
+                self.peak = self.peak.max(self.replies.len());
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Read for FakeServer {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.current.is_empty() {
+                let next = if self.reorder { self.replies.pop_back() } else { self.replies.pop_front() };
+                self.current = next.unwrap_or_default().into();
+            }
+            let n = buf.len().min(self.current.len());
+            for (b, v) in buf.iter_mut().zip(self.current.drain(..n)) {
+                *b = v;
+            }
+            Ok(n)
+        }
+    }
+
+    fn content(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn server_with(name: &str, data: Vec<u8>) -> FakeServer {
+        let mut server = FakeServer::default();
+        server.files.insert(name.as_bytes().to_vec(), data);
+        server
+    }
+
+    #[test]
+    fn download_keeps_window_full() {
+        let data = content(300_000 + 123);
+        let mut server = server_with("/f", data.clone());
+        let mut out = Vec::new();
+        let n = {
+            let mut pipe = Pipe::init(&mut server).unwrap();
+            pipe.with_file(Path::new("/f"), FXF_READ, |pipe, file| pipe.download(file, 0, None, &mut out, Window::new(4096, 8), &CancelToken::default())).unwrap()
+        };
+        assert_eq!((n, out), (data.len() as u64, data));
+        assert_eq!(server.peak, 8);
+        // Aakhri chhote DATA ke baad bacha hissa maanga jaata hai (jo EOF deta hai)
+        assert!(server.sizes.iter().all(|&s| s <= 4096) && server.sizes[0] == 4096);
+    }
+
+    #[test]
+    fn short_and_reordered_replies_are_reassembled() {
+        let data = content(100_000);
+        let mut server = server_with("/f", data.clone());
+        server.max_read = Some(1000);
+        server.reorder = true;
+        let mut pipe = Pipe::init(&mut server).unwrap();
+        let file = pipe.open(Path::new("/f"), FXF_READ).unwrap();
+        let mut out = Vec::new();
+        pipe.download(&file, 0, None, &mut out, Window::new(4096, 4), &CancelToken::default()).unwrap();
+        assert_eq!(out, data);
+        // Range: sirf [5000, 20000)
+        let mut out = Vec::new();
+        let n = pipe.download(&file, 5000, Some(20_000), &mut out, Window::new(4096, 4), &CancelToken::default()).unwrap();
+        assert_eq!((n, out.as_slice()), (15_000, &data[5000..20_000]));
+    }
+
+    #[test]
+    fn upload_pipelines_writes_and_drains_on_failure() {
+        let data = content(50_000);
+        let mut server = server_with("/f", Vec::new());
+        let n = {
+            let mut pipe = Pipe::init(&mut server).unwrap();
+            pipe.with_file(Path::new("/f"), FXF_WRITE, |pipe, file| pipe.upload(file, 0, &mut data.as_slice(), Window::new(8192, 4), &CancelToken::default())).unwrap()
+        };
+        assert_eq!(n, data.len() as u64);
+        assert_eq!(server.files[b"/f".as_slice()], data);
+        assert_eq!(server.peak, 4);
+        assert!(server.sizes[..6].iter().all(|&s| s == 8192));
+
+        // Beech me server fail kare: error aata hai lekin stream aage use ho sakta hai
+        server.fail_writes_from = Some(16_384);
+        let mut pipe = Pipe::init(&mut server).unwrap();
+        let file = pipe.open(Path::new("/f"), FXF_WRITE).unwrap();
+        let err = pipe.upload(&file, 0, &mut data.as_slice(), Window::new(8192, 4), &CancelToken::default()).unwrap_err();
+        assert!(matches!(&err, NetError::Ssh(e) if e.code() == ErrorCode::SFTP(4)), "{err:?}");
+        assert!(!pipe.is_broken());
+        pipe.close(file).unwrap();
+    }
+
+    #[test]
+    fn posix_rename_needs_the_advertised_extension() {
+        let mut server = server_with("/new", b"new".to_vec());
+        server.files.insert(b"/cur".to_vec(), b"old".to_vec());
+        let err = Pipe::init(&mut server).unwrap().posix_rename(Path::new("/new"), Path::new("/cur")).unwrap_err();
+        assert!(matches!(&err, NetError::Ssh(e) if e.code() == ErrorCode::SFTP(8)), "{err:?}");
+        assert_eq!(server.extended, 0);
+
+        server.extensions = vec!["statvfs@openssh.com", POSIX_RENAME];
+        let mut pipe = Pipe::init(&mut server).unwrap();
+        pipe.posix_rename(Path::new("/new"), Path::new("/cur")).unwrap();
+        // Source ab nahi hai: server ka status path ke saath
+        let err = pipe.posix_rename(Path::new("/new"), Path::new("/cur")).unwrap_err();
+        assert!(matches!(&err, NetError::NotFound(p) if p == Path::new("/new")), "{err:?}");
+        assert_eq!((server.extended, server.files[b"/cur".as_slice()].as_slice()), (2, b"new".as_slice()));
+    }
+}
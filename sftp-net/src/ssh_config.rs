@@ -0,0 +1,686 @@
+//! OpenSSH client config (`~/.ssh/config`) parser.
+//!
+//! Supported: `Host` patterns (`*`, `?`, `!negation`), basic `Match`
+//! (`all`, `host`, `originalhost`, `user`, `localuser`), `Include` (globs
+//! allowed), and the keywords HostName, Port, User, IdentityFile,
+//! UserKnownHostsFile, GlobalKnownHostsFile, HashKnownHosts,
+//! StrictHostKeyChecking, ProxyJump, ProxyCommand, ServerAliveInterval,
+//! KexAlgorithms, HostKeyAlgorithms, Ciphers, MACs and Compression. Baaki keywords ignore hote hain.
+//!
+//! OpenSSH ki tarah har keyword ki pehli value jeet-ti hai (IdentityFile
+//! accumulate hota hai).
+
+use crate::{Algorithms, Auth, HostKeyPolicy, KnownHosts, NetError, RefuseUnknownHost, RetryPolicy, SshConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Include recursion limit (OpenSSH bhi 16 rakhta hai).
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Parsed config file: blocks in file order.
+#[derive(Debug, Clone, Default)]
+pub struct OpenSshConfig {
+    blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone)]
+struct Block {
+    cond: Condition,
+    /// (lowercase keyword, args)
+    entries: Vec<(String, Vec<String>)>,
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    /// Pehle `Host` se pehle ki lines: sab pe apply hoti hain.
+    Always,
+    Host(Vec<String>),
+    Match(Vec<Criterion>),
+}
+
+#[derive(Debug, Clone)]
+enum Criterion {
+    All,
+    Host(Vec<String>),
+    OriginalHost(Vec<String>),
+    User(Vec<String>),
+    LocalUser(Vec<String>),
+    /// `exec`, `canonical` etc.: support nahi, isliye kabhi match nahi karta.
+    Unsupported,
+}
+
+/// Ek alias ke liye resolved settings.
+#[derive(Debug, Clone, Default)]
+pub struct HostSettings {
+    /// Jis naam se resolve hua (ProxyCommand ka `%n`).
+    pub alias: Option<String>,
+    pub host_name: Option<String>,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub identity_files: Vec<PathBuf>,
+    pub user_known_hosts_files: Vec<PathBuf>,
+    /// `ask` = `HostKeyPolicy::Ask(RefuseUnknownHost)`; interactive callers apna prompt lagate hain.
+    pub strict_host_key_checking: Option<HostKeyPolicy>,
+    /// `ProxyJump` value jaisa likha hai (`[user@]host[:port],...` ya `none`).
+    pub proxy_jump: Option<String>,
+    /// `ProxyCommand` poori line jaisi likhi hai; tokens connect ke time expand hote hain.
+    pub proxy_command: Option<String>,
+    /// `ServerAliveInterval` seconds (0 = off).
+    pub server_alive_interval: Option<u32>,
+    /// `GlobalKnownHostsFile` (khali = OpenSSH defaults).
+    pub global_known_hosts_files: Vec<PathBuf>,
+    /// `HashKnownHosts yes|no`.
+    pub hash_known_hosts: Option<bool>,
+    /// `KexAlgorithms`, `HostKeyAlgorithms`, `Ciphers`, `MACs` jaise likhe hain (`+`/`-`/`^` samet).
+    pub kex_algorithms: Option<String>,
+    pub host_key_algorithms: Option<String>,
+    pub ciphers: Option<String>,
+    pub macs: Option<String>,
+    /// `Compression yes|no`.
+    pub compression: Option<bool>,
+}
+
+impl OpenSshConfig {
+    /// Config text parse karo. `Include` relative paths `~/.ssh` se resolve hote hain.
+    pub fn parse(text: &str) -> Result<Self, NetError> {
+        let mut cfg = Self { blocks: vec![Block { cond: Condition::Always, entries: Vec::new() }] };
+        cfg.parse_into(text, 0)?;
+        Ok(cfg)
+    }
+
+    /// File se load karo.
+    pub fn load(path: &Path) -> Result<Self, NetError> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| NetError::Invalid(format!("cannot read ssh config {}: {e}", path.display())))?;
+        Self::parse(&text)
+    }
+
+    /// `~/.ssh/config` load karo; file na ho to khali config.
+    pub fn load_default() -> Result<Self, NetError> {
+        match home_dir().map(|h| h.join(".ssh").join("config")) {
+            Some(path) if path.exists() => Self::load(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    fn parse_into(&mut self, text: &str, depth: usize) -> Result<(), NetError> {
+        for (lineno, raw) in text.lines().enumerate() {
+            let Some((key, args)) = split_line(raw) else { continue };
+            match key.as_str() {
+                "host" => self.blocks.push(Block { cond: Condition::Host(args), entries: Vec::new() }),
+                "match" => {
+                    let criteria = parse_match(&args)
+                        .ok_or_else(|| NetError::Invalid(format!("ssh config line {}: bad Match", lineno + 1)))?;
+                    self.blocks.push(Block { cond: Condition::Match(criteria), entries: Vec::new() });
+                }
+                "include" => {
+                    if depth >= MAX_INCLUDE_DEPTH {
+                        return Err(NetError::Invalid("ssh config Include nested too deeply".into()));
+                    }
+                    // Included lines current block me inline hoti hain, OpenSSH jaisa
+                    let (cond, before) = (self.blocks.last().map(|b| b.cond.clone()), self.blocks.len());
+                    for pattern in &args {
+                        for file in expand_include(pattern) {
+                            if let Ok(inc) = fs::read_to_string(&file) {
+                                self.parse_into(&inc, depth + 1)?;
+                            }
+                        }
+                    }
+                    // Included file ne naye Host/Match khole hon to Include ke baad ki
+                    // lines phir se isi block ki hain, included ke aakhri block ki nahi
+                    if let Some(cond) = cond
+                        && self.blocks.len() != before
+                    {
+                        self.blocks.push(Block { cond, entries: Vec::new() });
+                    }
+                }
+                _ => {
+                    if let Some(block) = self.blocks.last_mut() {
+                        block.entries.push((key, args));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Kya koi `Host` block `alias` ko match karta hai? Akela `Host *` catch-all
+    /// nahi ginta, warna har `naam:path` operand alias lagta.
+    pub fn has_host(&self, alias: &str) -> bool {
+        self.blocks.iter().any(|block| match &block.cond {
+            Condition::Host(patterns) => match_list(patterns, alias) && patterns.iter().flat_map(|p| p.split(',')).any(|p| p != "*" && !p.starts_with('!')),
+            _ => false,
+        })
+    }
+
+    /// `alias` ke liye settings resolve karo (OpenSSH first-match-wins).
+    pub fn resolve(&self, alias: &str) -> HostSettings {
+        let local_user = local_user();
+        let mut out = HostSettings { alias: Some(alias.to_string()), ..Default::default() };
+        for block in &self.blocks {
+            let applies = match &block.cond {
+                Condition::Always => true,
+                Condition::Host(patterns) => match_list(patterns, alias),
+                Condition::Match(criteria) => {
+                    let host = out.host_name.as_deref().map(|h| expand_tokens(h, alias, alias, "", &local_user, 22));
+                    let host = host.as_deref().unwrap_or(alias);
+                    let user = out.user.as_deref().unwrap_or(&local_user);
+                    criteria.iter().all(|c| match c {
+                        Criterion::All => true,
+                        Criterion::Host(p) => match_list(p, host),
+                        Criterion::OriginalHost(p) => match_list(p, alias),
+                        Criterion::User(p) => match_list(p, user),
+                        Criterion::LocalUser(p) => match_list(p, &local_user),
+                        Criterion::Unsupported => false,
+                    })
+                }
+            };
+            if applies {
+                for (key, args) in &block.entries {
+                    out.apply(key, args);
+                }
+            }
+        }
+
+        // Tokens (%h, %r, %u, %d, %p, %n) ab expand karo jab saari values pata hain
+        let host = out.host_name.clone().map(|h| expand_tokens(&h, alias, alias, "", &local_user, 22)).unwrap_or_else(|| alias.to_string());
+        let user = out.user.clone().unwrap_or_else(|| local_user.clone());
+        let port = out.port.unwrap_or(22);
+        let expand_path = |p: &PathBuf| expand_tilde(&expand_tokens(&p.to_string_lossy(), &host, alias, &user, &local_user, port));
+        out.identity_files = out.identity_files.iter().map(expand_path).collect();
+        out.user_known_hosts_files = out.user_known_hosts_files.iter().map(expand_path).collect();
+        out.global_known_hosts_files = out.global_known_hosts_files.iter().map(expand_path).collect();
+        out.host_name = Some(host);
+        out
+    }
+
+    /// ProxyJump spec (`[user@]host[:port],...`) ko hop configs me badlo.
+    ///
+    /// Har hop ka host bhi isi config se resolve hota hai (bastion ka apna
+    /// HostName/User/IdentityFile). `auth = None` pe hop apni IdentityFile se,
+    /// warna ssh-agent se login karta hai. `auth` diya ho to wahi credentials
+    /// (password/key bhi) har bastion ko bheje jaate hain, isliye sirf tab do
+    /// jab bastion pe utna bharosa ho. `none` ka matlab koi jump nahi.
+    pub fn jump_hosts(&self, spec: &str, auth: Option<&Auth>) -> Result<Vec<SshConfig>, NetError> {
+        if spec.eq_ignore_ascii_case("none") {
+            return Ok(Vec::new());
+        }
+        spec.split(',')
+            .map(|hop| {
+                let (user, host, port) =
+                    parse_hop(hop.trim()).ok_or_else(|| NetError::Invalid(format!("bad jump host: {hop}")))?;
+                let mut settings = self.resolve(host);
+                if let Some(u) = user {
+                    settings.user = Some(u.to_string());
+                }
+                if let Some(p) = port {
+                    settings.port = Some(p);
+                }
+                let user = settings.user.clone().unwrap_or_else(local_user);
+                Ok(settings.to_ssh_config(auth.map(|a| a.with_username(&user))))
+            })
+            .collect()
+    }
+}
+
+/// `[user@]host[:port]`, IPv6 ke liye `[addr]:port`; `ssh://` prefix bhi chalega.
+fn parse_hop(hop: &str) -> Option<(Option<&str>, &str, Option<u16>)> {
+    let hop = hop.strip_prefix("ssh://").unwrap_or(hop);
+    let (user, rest) = match hop.rsplit_once('@') {
+        Some((u, r)) => (Some(u), r),
+        None => (None, hop),
+    };
+    let (host, port) = if let Some(v6) = rest.strip_prefix('[') {
+        let (host, tail) = v6.split_once(']')?;
+        match tail.strip_prefix(':') {
+            Some(p) => (host, Some(p.parse().ok()?)),
+            None if tail.is_empty() => (host, None),
+            None => return None,
+        }
+    } else {
+        match rest.split_once(':') {
+            Some((h, p)) => (h, Some(p.parse().ok()?)),
+            None => (rest, None),
+        }
+    };
+    if host.is_empty() || user == Some("") {
+        return None;
+    }
+    Some((user, host, port))
+}
+
+impl HostSettings {
+    fn apply(&mut self, key: &str, args: &[String]) {
+        let Some(first) = args.first() else { return };
+        match key {
+            "hostname" if self.host_name.is_none() => self.host_name = Some(first.clone()),
+            "port" if self.port.is_none() => self.port = first.parse().ok(),
+            "user" if self.user.is_none() => self.user = Some(first.clone()),
+            "identityfile" => {
+                let p = PathBuf::from(first);
+                if !self.identity_files.contains(&p) {
+                    self.identity_files.push(p);
+                }
+            }
+            "userknownhostsfile" if self.user_known_hosts_files.is_empty() => {
+                self.user_known_hosts_files = args.iter().map(PathBuf::from).collect();
+            }
+            "globalknownhostsfile" if self.global_known_hosts_files.is_empty() => {
+                self.global_known_hosts_files = args.iter().map(PathBuf::from).collect();
+            }
+            "hashknownhosts" if self.hash_known_hosts.is_none() => {
+                self.hash_known_hosts = match first.to_ascii_lowercase().as_str() {
+                    "yes" => Some(true),
+                    "no" => Some(false),
+                    _ => None,
+                };
+            }
+            "kexalgorithms" if self.kex_algorithms.is_none() => self.kex_algorithms = Some(first.clone()),
+            "hostkeyalgorithms" if self.host_key_algorithms.is_none() => self.host_key_algorithms = Some(first.clone()),
+            "ciphers" if self.ciphers.is_none() => self.ciphers = Some(first.clone()),
+            "macs" if self.macs.is_none() => self.macs = Some(first.clone()),
+            "compression" if self.compression.is_none() => {
+                self.compression = match first.to_ascii_lowercase().as_str() {
+                    "yes" => Some(true),
+                    "no" => Some(false),
+                    _ => None,
+                };
+            }
+            "stricthostkeychecking" if self.strict_host_key_checking.is_none() => {
+                self.strict_host_key_checking = match first.to_ascii_lowercase().as_str() {
+                    "yes" => Some(HostKeyPolicy::Strict),
+                    "ask" => Some(HostKeyPolicy::Ask(Arc::new(RefuseUnknownHost))),
+                    "accept-new" => Some(HostKeyPolicy::AcceptNew),
+                    "no" | "off" => Some(HostKeyPolicy::InsecureIgnore),
+                    _ => None,
+                };
+            }
+            "proxyjump" if self.proxy_jump.is_none() => self.proxy_jump = Some(first.clone()),
+            "proxycommand" if self.proxy_command.is_none() => self.proxy_command = Some(first.clone()),
+            "serveraliveinterval" if self.server_alive_interval.is_none() => self.server_alive_interval = first.parse().ok(),
+            _ => {}
+        }
+    }
+
+    /// In settings se `SshConfig` banao. `auth` na diya ho to pehli existing
+    /// IdentityFile (ya default `~/.ssh/id_*`) use hoti hai, warna ssh-agent.
+    pub fn to_ssh_config(&self, auth: Option<Auth>) -> SshConfig {
+        let host = self.host_name.clone().unwrap_or_default();
+        let user = self.user.clone().unwrap_or_else(local_user);
+        let auth = auth.unwrap_or_else(|| match self.default_identity() {
+            Some(key) => Auth::KeyFile { username: user.clone(), private_key: key, passphrase: None, public_key: None },
+            None => Auth::Agent { username: user.clone(), identity: None },
+        });
+        SshConfig {
+            host,
+            alias: self.alias.clone(),
+            port: self.port.unwrap_or(22),
+            auth,
+            known_hosts: self
+                .user_known_hosts_files
+                .first()
+                .cloned()
+                .or_else(|| home_dir().map(|h| h.join(".ssh").join("known_hosts"))),
+            // Baaki UserKnownHostsFile entries bhi sirf padhi jaati hain, global files ki tarah
+            global_known_hosts: self.user_known_hosts_files.iter().skip(1).cloned().chain(self.global_known_hosts()).collect(),
+            hash_known_hosts: self.hash_known_hosts.unwrap_or(false),
+            // Unset = OpenSSH ka default `ask`; bina prompt ke unknown host mana hi hota hai
+            hostkey_policy: self.strict_host_key_checking.clone().unwrap_or_else(|| HostKeyPolicy::Ask(Arc::new(RefuseUnknownHost))),
+            timeout_ms: Some(30_000),
+            jump_hosts: Vec::new(),
+            proxy_command: self.proxy_command.clone().filter(|c| !c.eq_ignore_ascii_case("none")),
+            proxy: None,
+            keepalive_secs: self.server_alive_interval.filter(|&s| s > 0),
+            retry: RetryPolicy::default(),
+            algorithms: self.algorithms(),
+        }
+    }
+
+    /// Algorithm keywords ko `Algorithms` me badlo (`Compression yes` = zlib pehle).
+    pub fn algorithms(&self) -> Algorithms {
+        Algorithms {
+            kex: self.kex_algorithms.clone(),
+            host_key: self.host_key_algorithms.clone(),
+            cipher: self.ciphers.clone(),
+            mac: self.macs.clone(),
+            compression: self.compression.filter(|&on| on).map(|_| Algorithms::ZLIB.to_string()),
+        }
+    }
+
+    /// `GlobalKnownHostsFile`, ya set na ho to OpenSSH ki default global files.
+    pub fn global_known_hosts(&self) -> Vec<PathBuf> {
+        if self.global_known_hosts_files.is_empty() {
+            KnownHosts::default_global_files()
+        } else {
+            self.global_known_hosts_files.clone()
+        }
+    }
+
+    /// Pehli IdentityFile jo disk pe hai; koi configured na ho to OpenSSH defaults.
+    pub fn default_identity(&self) -> Option<PathBuf> {
+        if !self.identity_files.is_empty() {
+            return self.identity_files.iter().find(|p| p.exists()).cloned();
+        }
+        let ssh_dir = home_dir()?.join(".ssh");
+        ["id_ed25519", "id_ecdsa", "id_rsa"].iter().map(|n| ssh_dir.join(n)).find(|p| p.exists())
+    }
+}
+
+/// `Key value`, `Key=value` aur quoted args handle karo; comments/blank skip.
+fn split_line(raw: &str) -> Option<(String, Vec<String>)> {
+    let line = raw.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let split_at = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let key = line[..split_at].to_ascii_lowercase();
+    let rest = line[split_at..].trim_start_matches(|c: char| c.is_whitespace() || c == '=');
+    // ProxyCommand shell ko jaisa ka taisa jaata hai, quotes ke saath
+    if key == "proxycommand" {
+        return Some((key, vec![rest.to_string()]));
+    }
+    Some((key, split_args(rest)))
+}
+
+fn split_args(s: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut buf = String::new();
+    let mut quoted = false;
+    for c in s.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !buf.is_empty() {
+                    out.push(std::mem::take(&mut buf));
+                }
+            }
+            c => buf.push(c),
+        }
+    }
+    if !buf.is_empty() {
+        out.push(buf);
+    }
+    out
+}
+
+fn parse_match(args: &[String]) -> Option<Vec<Criterion>> {
+    let mut out = Vec::new();
+    let mut it = args.iter();
+    while let Some(word) = it.next() {
+        let mut list = || it.next().map(|v| v.split(',').map(str::to_string).collect::<Vec<_>>());
+        out.push(match word.to_ascii_lowercase().as_str() {
+            "all" => Criterion::All,
+            "host" => Criterion::Host(list()?),
+            "originalhost" => Criterion::OriginalHost(list()?),
+            "user" => Criterion::User(list()?),
+            "localuser" => Criterion::LocalUser(list()?),
+            "canonical" | "final" => Criterion::Unsupported,
+            _ => {
+                let _ = list();
+                Criterion::Unsupported
+            }
+        });
+    }
+    Some(out)
+}
+
+/// Pattern list match: koi negated pattern match kare to false, warna koi bhi positive.
+pub(crate) fn match_list(patterns: &[String], value: &str) -> bool {
+    let mut matched = false;
+    for p in patterns.iter().flat_map(|p| p.split(',')) {
+        if let Some(neg) = p.strip_prefix('!') {
+            if glob_match(neg, value) {
+                return false;
+            }
+        } else if glob_match(p, value) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+/// `*` aur `?` wala glob (case-insensitive, hostnames ki tarah).
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let v: Vec<char> = value.to_lowercase().chars().collect();
+    let (mut pi, mut vi) = (0, 0);
+    let (mut star, mut mark) = (None, 0);
+    while vi < v.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == v[vi]) {
+            pi += 1;
+            vi += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some(pi);
+            mark = vi;
+            pi += 1;
+        } else if let Some(s) = star {
+            pi = s + 1;
+            mark += 1;
+            vi = mark;
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+/// Include path: `~` expand, relative ho to `~/.ssh/` se, filename me glob allowed.
+fn expand_include(pattern: &str) -> Vec<PathBuf> {
+    let mut path = expand_tilde(pattern);
+    if path.is_relative()
+        && let Some(home) = home_dir()
+    {
+        path = home.join(".ssh").join(path);
+    }
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    if !name.contains(['*', '?']) {
+        return vec![path];
+    }
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .map(|rd| {
+            rd.filter_map(Result::ok)
+                .filter(|e| glob_match(&name, &e.file_name().to_string_lossy()))
+                .map(|e| e.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+pub(crate) fn expand_tokens(s: &str, host: &str, alias: &str, user: &str, local_user: &str, port: u16) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('h') => out.push_str(host),
+            Some('n') => out.push_str(alias),
+            Some('r') => out.push_str(user),
+            Some('u') => out.push_str(local_user),
+            Some('p') => out.push_str(&port.to_string()),
+            Some('d') => out.push_str(&home_dir().unwrap_or_default().to_string_lossy()),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+fn expand_tilde(s: &str) -> PathBuf {
+    match (s.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ if s == "~" => home_dir().unwrap_or_else(|| PathBuf::from(s)),
+        _ => PathBuf::from(s),
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(PathBuf::from)
+}
+
+pub(crate) fn local_user() -> String {
+    std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+# global defaults come last in OpenSSH style configs
+Host web-*  !web-legacy
+    HostName %h.internal.example.com
+    User deploy
+    IdentityFile /keys/web
+
+Host bastion
+    HostName=10.0.0.5
+    Port 2222
+    StrictHostKeyChecking accept-new
+    UserKnownHostsFile "/tmp/known hosts" /tmp/team_hosts
+    GlobalKnownHostsFile /etc/ssh/corp_known_hosts
+    HashKnownHosts yes
+
+Host db
+    ProxyJump ops@bastion,[fd00::7]:2022
+
+Host tunnel
+    ProxyCommand sh -c "nc %h %p"
+    ServerAliveInterval 15
+    KexAlgorithms +diffie-hellman-group14-sha1
+    Ciphers aes128-ctr,aes256-ctr
+    Compression yes
+
+Match host *.internal.example.com user deploy
+    Port 2200
+
+Host *
+    User fallback
+    IdentityFile /keys/default
+    StrictHostKeyChecking yes
+"#;
+
+    #[test]
+    fn host_patterns_and_first_value_wins() {
+        let cfg = OpenSshConfig::parse(SAMPLE).unwrap();
+
+        let web = cfg.resolve("web-01");
+        assert_eq!(web.host_name.as_deref(), Some("web-01.internal.example.com"));
+        assert_eq!(web.user.as_deref(), Some("deploy"));
+        assert_eq!(web.port, Some(2200)); // via Match on resolved host + user
+        assert_eq!(web.identity_files, vec![PathBuf::from("/keys/web"), PathBuf::from("/keys/default")]);
+        assert!(matches!(web.strict_host_key_checking, Some(HostKeyPolicy::Strict)));
+
+        let ask = OpenSshConfig::parse("Host a\n  StrictHostKeyChecking ask\nHost *\n  StrictHostKeyChecking no\n").unwrap();
+        assert!(matches!(ask.resolve("a").strict_host_key_checking, Some(HostKeyPolicy::Ask(_))));
+        assert!(matches!(ask.resolve("b").strict_host_key_checking, Some(HostKeyPolicy::InsecureIgnore)));
+
+        let legacy = cfg.resolve("web-legacy");
+        assert_eq!(legacy.host_name.as_deref(), Some("web-legacy"));
+        assert_eq!(legacy.user.as_deref(), Some("fallback"));
+
+        let bastion = cfg.resolve("bastion");
+        assert_eq!(bastion.host_name.as_deref(), Some("10.0.0.5"));
+        assert_eq!(bastion.port, Some(2222));
+        assert_eq!(bastion.user_known_hosts_files, vec![PathBuf::from("/tmp/known hosts"), PathBuf::from("/tmp/team_hosts")]);
+        assert!(matches!(bastion.strict_host_key_checking, Some(HostKeyPolicy::AcceptNew)));
+
+        let ssh = bastion.to_ssh_config(None);
+        assert_eq!((ssh.host.as_str(), ssh.port), ("10.0.0.5", 2222));
+        assert_eq!(ssh.known_hosts, Some(PathBuf::from("/tmp/known hosts")));
+        assert_eq!(ssh.global_known_hosts, vec![PathBuf::from("/tmp/team_hosts"), PathBuf::from("/etc/ssh/corp_known_hosts")]);
+        assert!(ssh.hash_known_hosts);
+        assert!(!cfg.resolve("web-01").to_ssh_config(None).hash_known_hosts);
+    }
+
+    #[test]
+    fn has_host_skips_catch_all() {
+        let cfg = OpenSshConfig::parse(SAMPLE).unwrap();
+        assert!(cfg.has_host("db") && cfg.has_host("web-01"));
+        assert!(!cfg.has_host("web-legacy"));
+        assert!(!cfg.has_host("notes"));
+    }
+
+    #[test]
+    fn include_inlines_files() {
+        let dir = std::env::temp_dir().join(format!("sftp-net-sshcfg-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("10-db.conf"), "Host db\n  HostName db.example.com\n").unwrap();
+        let cfg = OpenSshConfig::parse(&format!("Include {}/*.conf\nHost *\n  Port 2022\n", dir.display())).unwrap();
+        let db = cfg.resolve("db");
+        assert_eq!(db.host_name.as_deref(), Some("db.example.com"));
+        assert_eq!(db.port, Some(2022));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn include_does_not_leak_block_context() {
+        let dir = std::env::temp_dir().join(format!("sftp-net-sshcfg-leak-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("db.conf"), "Host db\n  HostName db.example.com\n").unwrap();
+        let text = format!("Host web\n  Include {}/db.conf\n  User deploy\n  IdentityFile ~/.ssh/web\n", dir.display());
+        let cfg = OpenSshConfig::parse(&text).unwrap();
+        let web = cfg.resolve("web");
+        assert_eq!(web.user.as_deref(), Some("deploy"));
+        assert_eq!(web.identity_files.len(), 1);
+        let db = cfg.resolve("db");
+        assert_eq!(db.host_name.as_deref(), Some("db.example.com"));
+        assert_eq!(db.user, None);
+        assert!(db.identity_files.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn proxy_jump_resolves_each_hop() {
+        let cfg = OpenSshConfig::parse(SAMPLE).unwrap();
+        let spec = cfg.resolve("db").proxy_jump.unwrap();
+        let auth = Auth::Agent { username: "me".into(), identity: None };
+        let hops = cfg.jump_hosts(&spec, Some(&auth)).unwrap();
+        assert_eq!(hops.len(), 2);
+        assert!(matches!(&hops[0].auth, Auth::Agent { username, .. } if username == "ops"));
+
+        // Default: target ke credentials bastion tak nahi jaate, hop apni IdentityFile use karta hai
+        let key = std::env::temp_dir().join(format!("sftp-net-hopkey-{}", std::process::id()));
+        fs::write(&key, "not really a key").unwrap();
+        let own = OpenSshConfig::parse(&format!("Host bastion\n  IdentityFile {}\n", key.display())).unwrap();
+        let own = own.jump_hosts("ops@bastion", None).unwrap();
+        assert!(matches!(&own[0].auth, Auth::KeyFile { username, private_key, .. } if username == "ops" && *private_key == key));
+        // StrictHostKeyChecking unset = OpenSSH default `ask`
+        assert!(matches!(own[0].hostkey_policy, HostKeyPolicy::Ask(_)));
+        fs::remove_file(&key).unwrap();
+        assert_eq!((hops[0].host.as_str(), hops[0].port, hops[0].auth.username()), ("10.0.0.5", 2222, "ops"));
+        assert!(matches!(hops[0].hostkey_policy, HostKeyPolicy::AcceptNew));
+        assert_eq!((hops[1].host.as_str(), hops[1].port, hops[1].auth.username()), ("fd00::7", 2022, "fallback"));
+
+        assert!(cfg.jump_hosts("none", None).unwrap().is_empty());
+        assert!(cfg.jump_hosts("user@", None).is_err());
+        assert!(cfg.jump_hosts("host:notaport", None).is_err());
+
+        let tunnel = cfg.resolve("tunnel").to_ssh_config(None);
+        assert_eq!(tunnel.proxy_command.as_deref(), Some(r#"sh -c "nc %h %p""#));
+        assert_eq!(tunnel.keepalive_secs, Some(15));
+        assert_eq!(tunnel.algorithms.kex.as_deref(), Some("+diffie-hellman-group14-sha1"));
+        assert_eq!(tunnel.algorithms.cipher.as_deref(), Some("aes128-ctr,aes256-ctr"));
+        assert_eq!(tunnel.algorithms.compression.as_deref(), Some(Algorithms::ZLIB));
+        assert_eq!(tunnel.algorithms.mac, None);
+    }
+
+    #[test]
+    fn glob_and_negation() {
+        assert!(glob_match("*.example.com", "a.EXAMPLE.com"));
+        assert!(glob_match("db?", "db1"));
+        assert!(!glob_match("db?", "db12"));
+        assert!(!match_list(&["*".into(), "!secret".into()], "secret"));
+        assert!(match_list(&["foo,bar".into()], "bar"));
+    }
+}
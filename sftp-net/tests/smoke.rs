@@ -1,7 +1,7 @@
 // Integration smoke test: runs only if SFTP_TEST_* env vars are set.
 // Hinglish: Agar env vars nahi mile to test gracefully pass ho jayega.
 
-use sftp_net::{Auth, HostKeyPolicy, SshConfig, SftpClient};
+use sftp_net::{Algorithms, Auth, HostKeyPolicy, KnownHosts, OpenSshConfig, RetryPolicy, SshConfig, SftpClient};
 
 #[test]
 fn sftp_smoke_env() -> Result<(), Box<dyn std::error::Error>> {
@@ -20,7 +20,9 @@ fn sftp_smoke_env() -> Result<(), Box<dyn std::error::Error>> {
         HostKeyPolicy::Strict
     };
 
-    let auth = if let Some(k) = key {
+    let auth = if env::var("SFTP_TEST_AGENT").ok().as_deref() == Some("1") {
+        Auth::Agent { username: user.clone(), identity: None }
+    } else if let Some(k) = key {
         Auth::KeyFile { username: user.clone(), private_key: k.into(), passphrase: key_pass, public_key: None }
     } else if let Some(pw) = password {
         Auth::Password { username: user.clone(), password: pw }
@@ -28,13 +30,32 @@ fn sftp_smoke_env() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     };
 
+    // SFTP_TEST_JUMP=user@bastion:port: same credentials aur known_hosts ke saath ProxyJump
+    let mut jump_hosts = match env::var("SFTP_TEST_JUMP") {
+        Ok(spec) => OpenSshConfig::default().jump_hosts(&spec, Some(&auth))?,
+        Err(_) => Vec::new(),
+    };
+    for hop in &mut jump_hosts {
+        hop.known_hosts = known_hosts.clone().map(Into::into);
+        hop.hostkey_policy = policy.clone();
+    }
+
     let cfg = SshConfig {
         host,
+        alias: None,
         port,
         auth,
         known_hosts: known_hosts.map(Into::into),
+        global_known_hosts: KnownHosts::default_global_files(),
+        hash_known_hosts: false,
         hostkey_policy: policy,
         timeout_ms: Some(30_000),
+        jump_hosts,
+        proxy_command: env::var("SFTP_TEST_PROXY_COMMAND").ok(),
+        proxy: env::var("SFTP_TEST_PROXY").ok().map(|p| p.parse()).transpose()?,
+        keepalive_secs: None,
+        retry: RetryPolicy::default(),
+        algorithms: Algorithms::default(),
     };
 
     let client = SftpClient::connect(&cfg)?;
@@ -3,6 +3,10 @@
 use std::{io, path::PathBuf};
 use thiserror::Error;
 use std::{fs, io::{Read, Write}, path::Path};
+use std::time::UNIX_EPOCH;
+use sha2::{Digest, Sha256};
+use sftp_core::{check_digest, hex, sha256_hex, DigestMismatch};
+pub use sftp_core::{DirEntry, FileKind};
 
 #[derive(Debug, Error)]
 pub enum TransferError{
@@ -10,10 +14,18 @@ pub enum TransferError{
     FileNotFound(PathBuf),
     #[error("permission denied: {0:?}")]
     PermissionDenied(PathBuf),
+    #[error("checksum mismatch for {path:?}: source sha256 {expected}, destination sha256 {actual}")]
+    ChecksumMismatch { path: PathBuf, expected: String, actual: String },
     #[error("io error: {0}")]
     Io(#[from] io::Error),
 }
 
+impl From<DigestMismatch> for TransferError {
+    fn from(m: DigestMismatch) -> Self {
+        TransferError::ChecksumMismatch { path: m.path, expected: m.expected, actual: m.actual }
+    }
+}
+
 
 pub struct TransferProgress {
     pub bytes_transferred: usize,
@@ -41,20 +53,42 @@ impl TransferProgress{
     }
 }
 
+/// `upload_file_with` / `download_file_with` ke optional modes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CopyOptions {
+    /// SHA-256 check: source copy ke saath hash, destination dobara padh ke compare.
+    pub verify: bool,
+    /// `cp -p` jaisa: atime/mtime aur permissions source se dest pe copy karo.
+    pub preserve: bool,
+}
+
 pub struct TransferManager;
 
 impl TransferManager{
     pub fn upload_file(src:&Path , dest:&Path) -> Result< TransferProgress , TransferError > {
+        Self::upload_file_with(src, dest, CopyOptions::default())
+    }
+
+    /// `upload_file` + SHA-256 check: source copy ke saath hash hota hai,
+    /// destination baad me dobara padh ke compare.
+    pub fn upload_file_verified(src: &Path, dest: &Path) -> Result<TransferProgress, TransferError> {
+        Self::upload_file_with(src, dest, CopyOptions { verify: true, ..Default::default() })
+    }
+
+    pub fn upload_file_with(src: &Path, dest: &Path, opts: CopyOptions) -> Result<TransferProgress, TransferError> {
         if !src.exists(){
             return Err(TransferError::FileNotFound(src.to_path_buf()));
         }
 
-        let total_size = fs::metadata(src)?.len() as usize;
+        // Copy se pehle, taaki preserve ko hamare read se pehle wala atime mile
+        let meta = fs::metadata(src)?;
+        let total_size = meta.len() as usize;
         let mut progress = TransferProgress::new(total_size);
 
         let mut src_file = fs::File::open(src)?;
         let mut dest_file = fs::File::create(dest)?;
         let mut buffer = [0u8; 8192];
+        let mut hasher = Sha256::new();
 
         loop {
             let bytes_read = src_file.read(&mut buffer)?;
@@ -62,21 +96,37 @@ impl TransferManager{
                 break;
             }
             dest_file.write_all(&buffer[..bytes_read])?;
+            if opts.verify {
+                hasher.update(&buffer[..bytes_read]);
+            }
             progress.update(bytes_read);
             println!("Progress: {:.2}%", progress.percentage());
         }
 
+        drop(dest_file);
+        finish(&meta, dest, hasher, opts)?;
         Ok(progress)
     }
 
     pub fn download_file(src:&Path , dest:&Path) -> Result<(), TransferError> {
+        Self::download_file_with(src, dest, CopyOptions::default())
+    }
+
+    /// `download_file` + SHA-256 check, `upload_file_verified` jaisa.
+    pub fn download_file_verified(src: &Path, dest: &Path) -> Result<(), TransferError> {
+        Self::download_file_with(src, dest, CopyOptions { verify: true, ..Default::default() })
+    }
+
+    pub fn download_file_with(src: &Path, dest: &Path, opts: CopyOptions) -> Result<(), TransferError> {
    
     if !src.exists(){
         return Err(TransferError::FileNotFound(src.to_path_buf()));
     }
+    let meta = fs::metadata(src)?;
     let mut src_file = fs::File::open(src)?;
     let mut dest_file = fs::File::create(dest)?;
     let mut buffer = [0u8; 8192];
+    let mut hasher = Sha256::new();
 
     loop {
         let bytes_read = src_file.read(&mut buffer)?;
@@ -84,9 +134,13 @@ impl TransferManager{
             break;
         }
         dest_file.write_all(&buffer[..bytes_read])?;
+        if opts.verify {
+            hasher.update(&buffer[..bytes_read]);
+        }
     }
 
-    Ok(())
+    drop(dest_file);
+    finish(&meta, dest, hasher, opts)
   }
 
     pub fn list_files(dir: &Path) -> Result<Vec<String>, TransferError> {
@@ -102,6 +156,143 @@ impl TransferManager{
     Ok(files)
 }
 
+    /// Like `list_files`, but keeps size, permissions, owner and mtime per entry.
+    pub fn list_entries(dir: &Path) -> Result<Vec<DirEntry>, TransferError> {
+        if !dir.exists() {
+            return Err(TransferError::FileNotFound(dir.to_path_buf()));
+        }
+
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            // symlink_metadata so links show up as links, like `ls -l`
+            let meta = fs::symlink_metadata(entry.path())?;
+            entries.push(local_entry(entry.file_name().to_string_lossy().into_owned(), &meta));
+        }
+        Ok(entries)
+    }
+
+}
+
+/// Copy ke baad verify, phir preserve (verify ka read atime na bigaade).
+fn finish(meta: &fs::Metadata, dest: &Path, hasher: Sha256, opts: CopyOptions) -> Result<(), TransferError> {
+    if opts.verify {
+        verify_dest(dest, hasher)?;
+    }
+    if opts.preserve {
+        let mut times = fs::FileTimes::new().set_modified(meta.modified()?);
+        if let Ok(atime) = meta.accessed() {
+            times = times.set_accessed(atime);
+        }
+        fs::OpenOptions::new().write(true).open(dest)?.set_times(times)?;
+        fs::set_permissions(dest, meta.permissions())?;
+    }
+    Ok(())
+}
+
+/// Destination ko dobara padh ke uska digest source wale `hasher` se milao.
+fn verify_dest(dest: &Path, hasher: Sha256) -> Result<(), TransferError> {
+    let actual = sha256_hex(fs::File::open(dest)?)?;
+    Ok(check_digest(dest, hex(&hasher.finalize()), actual)?)
+}
+
+fn local_entry(name: String, meta: &fs::Metadata) -> DirEntry {
+    let ft = meta.file_type();
+    let kind = if ft.is_dir() {
+        FileKind::Dir
+    } else if ft.is_symlink() {
+        FileKind::Symlink
+    } else if ft.is_file() {
+        FileKind::File
+    } else {
+        FileKind::Other
+    };
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    #[cfg(unix)]
+    let (perm, uid, gid) = {
+        use std::os::unix::fs::MetadataExt;
+        (Some(meta.mode() & 0o7777), Some(meta.uid()), Some(meta.gid()))
+    };
+    #[cfg(not(unix))]
+    let (perm, uid, gid) = {
+        let perm = if meta.permissions().readonly() { 0o444 } else { 0o644 };
+        (Some(perm), None, None)
+    };
+
+    DirEntry { name, kind, size: meta.len(), perm, uid, gid, mtime }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sftp-transfer-{tag}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn verified_copies_match_source() {
+        let dir = temp_dir("verified");
+        let src = dir.join("src.bin");
+        let data: Vec<u8> = (0..100_003u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&src, &data).unwrap();
 
+        let progress = TransferManager::upload_file_verified(&src, &dir.join("up.bin")).unwrap();
+        assert_eq!(progress.bytes_transferred, data.len());
+        assert_eq!(fs::read(dir.join("up.bin")).unwrap(), data);
+
+        TransferManager::download_file_verified(&src, &dir.join("down.bin")).unwrap();
+        assert_eq!(fs::read(dir.join("down.bin")).unwrap(), data);
+
+        let missing = dir.join("missing.bin");
+        assert!(matches!(TransferManager::upload_file_verified(&missing, &dir.join("x")), Err(TransferError::FileNotFound(_))));
+        assert!(matches!(TransferManager::download_file_verified(&missing, &dir.join("x")), Err(TransferError::FileNotFound(_))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn digest_mismatch_is_reported() {
+        let dir = temp_dir("mismatch");
+        let dest = dir.join("dest.bin");
+        fs::write(&dest, b"what landed on disk").unwrap();
+
+        let mut source = Sha256::new();
+        source.update(b"what landed on disk");
+        verify_dest(&dest, source).unwrap();
+
+        let mut source = Sha256::new();
+        source.update(b"what was read from the source");
+        let err = verify_dest(&dest, source).unwrap_err();
+        assert!(matches!(err, TransferError::ChecksumMismatch { ref path, .. } if *path == dest));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn preserve_copies_mode_and_mtime() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = temp_dir("preserve");
+        let src = dir.join("src.txt");
+        fs::write(&src, b"keep my metadata").unwrap();
+        let old = UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        fs::OpenOptions::new().write(true).open(&src).unwrap().set_times(fs::FileTimes::new().set_modified(old)).unwrap();
+        fs::set_permissions(&src, fs::Permissions::from_mode(0o640)).unwrap();
+        let opts = CopyOptions { preserve: true, ..Default::default() };
+
+        TransferManager::upload_file_with(&src, &dir.join("up.txt"), opts).unwrap();
+        TransferManager::download_file_with(&src, &dir.join("down.txt"), opts).unwrap();
+        for dest in ["up.txt", "down.txt"] {
+            let meta = fs::metadata(dir.join(dest)).unwrap();
+            assert_eq!(meta.modified().unwrap(), old, "{dest}");
+            assert_eq!(meta.permissions().mode() & 0o7777, 0o640, "{dest}");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}